@@ -5,6 +5,7 @@ use crate::event_loop::ActiveEventLoop;
 use crate::window::WindowId;
 
 pub mod macos;
+pub mod winrt;
 
 /// The handler of application-level events.
 pub trait ApplicationHandler {
@@ -51,6 +52,11 @@ pub trait ApplicationHandler {
     ///
     /// [`onStart()`]: https://developer.android.com/reference/android/app/Activity#onStart()
     ///
+    /// ### WinRT
+    ///
+    /// On WinRT, the [`resumed()`] method is called in response to the
+    /// `CoreApplication::LeavingBackground` event.
+    ///
     /// ### Others
     ///
     /// **macOS / Orbital / Wayland / Windows / X11:** Unsupported.
@@ -261,6 +267,12 @@ pub trait ApplicationHandler {
     ///
     /// [`onStop()`]: https://developer.android.com/reference/android/app/Activity#onStop()
     ///
+    /// ### WinRT
+    ///
+    /// On WinRT, the [`suspended()`] method is called in response to the
+    /// `CoreApplication::EnteredBackground` event. The event's deferral is held until this method
+    /// returns.
+    ///
     /// ### Others
     ///
     /// **macOS / Orbital / Wayland / Windows / X11:** Unsupported.
@@ -298,6 +310,13 @@ pub trait ApplicationHandler {
     /// [`VkSurfaceKHR`]: https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkSurfaceKHR.html
     /// [`wgpu::Surface`]: https://docs.rs/wgpu/latest/wgpu/struct.Surface.html
     ///
+    /// ### WinRT
+    ///
+    /// On WinRT, the [`destroy_surfaces()`] method is called in response to the
+    /// `CoreApplication::Suspending` event, and [`can_create_surfaces()`] is called again once the
+    /// application is resumed. The suspending deferral is held until this method returns, giving
+    /// the application a chance to save its state.
+    ///
     /// ### Others
     ///
     /// - **iOS / macOS / Orbital / Wayland / Web / Windows / X11:** Unsupported.
@@ -343,6 +362,14 @@ pub trait ApplicationHandler {
     fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
         None
     }
+
+    /// The WinRT-specific handler.
+    ///
+    /// The return value from this should not change at runtime.
+    #[inline(always)]
+    fn winrt_handler(&mut self) -> Option<&mut dyn winrt::ApplicationHandlerExtWinRt> {
+        None
+    }
 }
 
 #[deny(clippy::missing_trait_methods)]
@@ -411,6 +438,11 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
     fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
         (**self).macos_handler()
     }
+
+    #[inline]
+    fn winrt_handler(&mut self) -> Option<&mut dyn winrt::ApplicationHandlerExtWinRt> {
+        (**self).winrt_handler()
+    }
 }
 
 #[deny(clippy::missing_trait_methods)]
@@ -479,4 +511,9 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
     fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
         (**self).macos_handler()
     }
+
+    #[inline]
+    fn winrt_handler(&mut self) -> Option<&mut dyn winrt::ApplicationHandlerExtWinRt> {
+        (**self).winrt_handler()
+    }
 }
@@ -0,0 +1,259 @@
+use dpi::{PhysicalPosition, PhysicalSize};
+
+use crate::application::ApplicationHandler;
+use crate::event::ElementState;
+use crate::event_loop::ActiveEventLoop;
+use crate::window::WindowId;
+
+/// Additional events on [`ApplicationHandler`] that are specific to WinRT/UWP.
+///
+/// This can be registered with [`ApplicationHandler::winrt_handler`].
+pub trait ApplicationHandlerExtWinRt: ApplicationHandler {
+    /// The effective layout direction of the application changed.
+    ///
+    /// `right_to_left` is `true` when the user interface should be mirrored, e.g. because the
+    /// primary application language is Arabic or Hebrew.
+    ///
+    /// This is raised when the qualifier values of the view's `ResourceContext` change.
+    fn layout_direction_changed(&mut self, event_loop: &dyn ActiveEventLoop, right_to_left: bool) {
+        let _ = event_loop;
+        let _ = right_to_left;
+    }
+
+    /// The area of the window occluded by the input pane (the touch keyboard) changed.
+    ///
+    /// `position` and `size` describe the occluded rectangle relative to the window. The size is
+    /// zero when the input pane was hidden.
+    ///
+    /// This can be used to scroll the focused text field into view. winit tells the system that the
+    /// application takes care of this itself, so the window is not panned automatically.
+    fn input_pane_occlusion_changed(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        position: PhysicalPosition<i32>,
+        size: PhysicalSize<u32>,
+    ) {
+        let _ = event_loop;
+        let _ = window_id;
+        let _ = position;
+        let _ = size;
+    }
+
+    /// The layout of the title bar changed, e.g. because the caption buttons were resized.
+    ///
+    /// This is raised by `CoreApplicationViewTitleBar::LayoutMetricsChanged`. Applications that
+    /// extend into the title bar should query the new caption button insets with
+    /// `WindowExtWinRt::title_bar_metrics` and lay out their custom chrome again.
+    fn title_bar_layout_changed(&mut self, event_loop: &dyn ActiveEventLoop, window_id: WindowId) {
+        let _ = event_loop;
+        let _ = window_id;
+    }
+
+    /// The user turned animations in the user interface on or off.
+    ///
+    /// `enabled` is `false` when the user asked for reduced motion, in which case the application
+    /// should avoid non-essential animations. This is raised by
+    /// `UISettings::AnimationsEnabledChanged`.
+    fn animations_enabled_changed(&mut self, event_loop: &dyn ActiveEventLoop, enabled: bool) {
+        let _ = event_loop;
+        let _ = enabled;
+    }
+
+    /// The advanced color state of the display changed, e.g. because HDR was turned on or the user
+    /// moved the SDR content brightness slider.
+    ///
+    /// `sdr_white_level` is the new luminance in nits that SDR content is mapped to, or `0.0` if
+    /// the display doesn't report it. The other values can be queried with
+    /// `MonitorHandleExtWinRt::advanced_color_info`. This is raised by
+    /// `DisplayInformation::AdvancedColorInfoChanged`.
+    fn advanced_color_info_changed(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        sdr_white_level: f32,
+    ) {
+        let _ = event_loop;
+        let _ = window_id;
+        let _ = sdr_white_level;
+    }
+
+    /// The color profile of the display changed.
+    ///
+    /// The new profile can be read with `DisplayInformation::GetColorProfileAsync`. This is raised
+    /// by `DisplayInformation::ColorProfileChanged`.
+    fn color_profile_changed(&mut self, event_loop: &dyn ActiveEventLoop, window_id: WindowId) {
+        let _ = event_loop;
+        let _ = window_id;
+    }
+
+    /// The orientation of the display changed, e.g. because the device was rotated.
+    ///
+    /// DirectX applications that pre-rotate their frames should query the new rotation with
+    /// `WindowExtWinRt::surface_rotation`. This is raised by
+    /// `DisplayInformation::OrientationChanged`.
+    fn surface_rotation_changed(&mut self, event_loop: &dyn ActiveEventLoop, window_id: WindowId) {
+        let _ = event_loop;
+        let _ = window_id;
+    }
+
+    /// The application was launched, or launched again while it was running, e.g. from a
+    /// secondary tile.
+    ///
+    /// `arguments` are the arguments passed to the application, and `tile_id` is the ID of the tile
+    /// the application was launched from, `"App"` for the primary tile. This is raised by
+    /// `CoreApplicationView::Activated` with `LaunchActivatedEventArgs`.
+    fn launch_activated(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        arguments: &str,
+        tile_id: &str,
+    ) {
+        let _ = event_loop;
+        let _ = arguments;
+        let _ = tile_id;
+    }
+
+    /// The window was activated, right before [`WindowEvent::Focused`] with `true`.
+    ///
+    /// `pointer_activated` is `true` when the window was activated by a pointer click, in which
+    /// case the following pointer events belong to that click and may be ignored, e.g. so that
+    /// the click which focused the window doesn't trigger a button. It is `false` when the
+    /// window was activated by code or the keyboard. This is raised by `CoreWindow::Activated`.
+    ///
+    /// [`WindowEvent::Focused`]: crate::event::WindowEvent::Focused
+    fn window_activated(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        pointer_activated: bool,
+    ) {
+        let _ = event_loop;
+        let _ = window_id;
+        let _ = pointer_activated;
+    }
+
+    /// The input language changed, e.g. because the user switched it with Win+Space.
+    ///
+    /// `language` is the BCP-47 tag of the new input language, e.g. `"de-DE"`. The keyboard layout
+    /// follows the input language, so the [`logical_key`] of a key may differ afterwards and cached
+    /// shortcut labels should be refreshed. This is raised by
+    /// `CoreTextServicesManager::InputLanguageChanged`.
+    ///
+    /// [`logical_key`]: crate::event::KeyEvent::logical_key
+    fn input_language_changed(&mut self, event_loop: &dyn ActiveEventLoop, language: &str) {
+        let _ = event_loop;
+        let _ = language;
+    }
+
+    /// The content of the clipboard changed, in this or another application.
+    ///
+    /// This is raised by `Clipboard::ContentChanged`.
+    fn clipboard_content_changed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let _ = event_loop;
+    }
+
+    /// A camera was added to the holographic space of the window, e.g. because a headset was
+    /// connected.
+    ///
+    /// The camera is available through `WindowExtWinRt::holographic_camera`, and isn't used for
+    /// rendering before this returns, so resources for it can be created here. This is raised by
+    /// `HolographicSpace::CameraAdded`.
+    fn holographic_camera_added(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        camera_id: u32,
+    ) {
+        let _ = event_loop;
+        let _ = window_id;
+        let _ = camera_id;
+    }
+
+    /// The state of a spatial interaction source changed, e.g. the user air-tapped.
+    ///
+    /// This is only raised in holographic mode, see `EventLoopBuilderExtWinRt::with_holographic`.
+    /// The poses of the sources aren't included, since they are relative to a coordinate system
+    /// of the application; they can be queried with
+    /// `SpatialInteractionManager::GetDetectedSourcesAtTimestamp`. This is raised by the
+    /// `SpatialInteractionManager::Source*` events.
+    fn spatial_input(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        event: SpatialInputEvent,
+    ) {
+        let _ = event_loop;
+        let _ = window_id;
+        let _ = event;
+    }
+
+    /// A camera was removed from the holographic space of the window, and the resources created
+    /// for it should be released.
+    ///
+    /// This is raised by `HolographicSpace::CameraRemoved`.
+    fn holographic_camera_removed(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        camera_id: u32,
+    ) {
+        let _ = event_loop;
+        let _ = window_id;
+        let _ = camera_id;
+    }
+
+    /// The user started resizing the window interactively.
+    ///
+    /// Until [`resize_completed`] is called, the window is resized repeatedly with
+    /// [`WindowEvent::SurfaceResized`], so expensive relayouts can be deferred. This is raised by
+    /// `CoreWindow::ResizeStarted`.
+    ///
+    /// [`resize_completed`]: Self::resize_completed
+    /// [`WindowEvent::SurfaceResized`]: crate::event::WindowEvent::SurfaceResized
+    fn resize_started(&mut self, event_loop: &dyn ActiveEventLoop, window_id: WindowId) {
+        let _ = event_loop;
+        let _ = window_id;
+    }
+
+    /// The user finished resizing the window interactively.
+    ///
+    /// This is raised by `CoreWindow::ResizeCompleted`.
+    fn resize_completed(&mut self, event_loop: &dyn ActiveEventLoop, window_id: WindowId) {
+        let _ = event_loop;
+        let _ = window_id;
+    }
+}
+
+/// A change of a spatial interaction source, see [`ApplicationHandlerExtWinRt::spatial_input`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpatialInputEvent {
+    /// A source was detected, e.g. a hand came into view or a controller was turned on.
+    SourceDetected { source_id: u32, kind: SpatialSourceKind },
+    /// A source is no longer detected.
+    SourceLost { source_id: u32 },
+    /// A button of a source was pressed or released, e.g. [`SpatialPress::Select`] for an air
+    /// tap.
+    Button { source_id: u32, press: SpatialPress, state: ElementState },
+}
+
+/// The kind of a spatial interaction source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpatialSourceKind {
+    Hand,
+    Voice,
+    Controller,
+    Other,
+}
+
+/// The button of a spatial interaction source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpatialPress {
+    /// The air tap of a hand, "select" said by voice, or the trigger of a controller.
+    Select,
+    Menu,
+    Grasp,
+    Touchpad,
+    Thumbstick,
+    Other,
+}
@@ -66,6 +66,8 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
+    /// - **WinRT:** Best-effort, the position is only checked when the size or the visible bounds
+    ///   of the view change.
     /// - **iOS / Android / Web / Wayland:** Unsupported.
     Moved(PhysicalPosition<i32>),
 
@@ -267,8 +269,9 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS**, **iOS**, and **Wayland**.
+    /// - Only available on **macOS**, **iOS**, **Wayland**, and **WinRT**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On WinRT, only recognized for touch input.
     PinchGesture {
         device_id: Option<DeviceId>,
         /// Positive values indicate magnification (zooming in) and  negative
@@ -283,8 +286,9 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **iOS** and **Wayland**.
+    /// - Only available on **iOS**, **Wayland**, and **WinRT**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On WinRT, only recognized for touch input.
     PanGesture {
         device_id: Option<DeviceId>,
         /// Change in pixels of pan gesture from last update.
@@ -308,8 +312,9 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS 10.8** and later, and **iOS**.
+    /// - Only available on **macOS 10.8** and later, **iOS**, and **WinRT**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On WinRT, this is a double tap with a single finger.
     DoubleTapGesture { device_id: Option<DeviceId> },
 
     /// Two-finger rotation gesture.
@@ -319,8 +324,9 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS**, **iOS**, and **Wayland**.
+    /// - Only available on **macOS**, **iOS**, **Wayland**, and **WinRT**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On WinRT, only recognized for touch input.
     RotationGesture {
         device_id: Option<DeviceId>,
         /// change in rotation in degrees
@@ -402,6 +408,8 @@ pub enum WindowEvent {
     /// ### Others
     ///
     /// - **Web:** Doesn't take into account CSS [`border`], [`padding`], or [`transform`].
+    /// - **WinRT:** Follows `CoreWindow::Visible`, i.e. the window is occluded while the
+    ///   application is minimized or hidden.
     /// - **Android / Wayland / Windows / Orbital:** Unsupported.
     ///
     /// [`border`]: https://developer.mozilla.org/en-US/docs/Web/CSS/border
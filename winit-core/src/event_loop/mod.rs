@@ -71,8 +71,11 @@ pub trait ActiveEventLoop: AsAny + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **Wayland / macOS / iOS / Android / Orbital:** Unsupported.
+    /// - **WinRT:** Only affects [`DeviceEvent::PointerMotion`], which is reported while the
+    ///   application is in the foreground.
     ///
     /// [`DeviceEvent`]: crate::event::DeviceEvent
+    /// [`DeviceEvent::PointerMotion`]: crate::event::DeviceEvent::PointerMotion
     fn listen_device_events(&self, allowed: DeviceEvents);
 
     /// Returns the current system theme.
@@ -67,6 +67,7 @@ pub trait EventLoopExtPumpEvents {
     /// - Linux
     /// - MacOS
     /// - Android
+    /// - WinRT, from the view thread (see `EventLoopViewExtWinRt::run_view_never_return`)
     ///
     /// ## Unsupported Platforms
     ///
@@ -83,6 +84,9 @@ pub trait EventLoopExtPumpEvents {
     /// - **Windows**: The implementation will use `PeekMessage` when checking for window messages
     ///   to avoid blocking your external event loop.
     ///
+    /// - **WinRT**: `CoreDispatcher` can't wait for events with a timeout, so the implementation
+    ///   uses a `ThreadPoolTimer` to stop processing events once the timeout elapsed.
+    ///
     /// - **MacOS**: The implementation works in terms of stopping the global application whenever
     ///   the application `RunLoop` indicates that it is preparing to block and wait for new events.
     ///
@@ -601,6 +601,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// - **Android / iOS / X11 / Web / Windows / macOS / Orbital:** Unsupported.
     /// - **Wayland:** Schedules a frame callback to throttle [`WindowEvent::RedrawRequested`].
+    /// - **WinRT:** Holds back the next [`WindowEvent::RedrawRequested`] until the vertical blank.
     ///
     /// [`WindowEvent::RedrawRequested`]: crate::event::WindowEvent::RedrawRequested
     fn pre_present_notify(&self);
@@ -613,6 +614,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// ## Platform-specific
     /// - **Web, macOS:** Does nothing
+    /// - **WinRT:** Emits a dead key that is still waiting for its character, but the system may
+    ///   still combine it with the next key press.
     // ---------------------------
     // Developers' Note: If this cannot be implemented on every desktop platform
     // at least, then this function should be provided through a platform specific
@@ -651,6 +654,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **Web:** Returns the top-left coordinates relative to the viewport.
+    /// - **WinRT:** Returns the position of the surface, the title bar is not included.
     /// - **Android / Wayland:** Always returns [`RequestError::NotSupported`].
     fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError>;
 
@@ -769,6 +773,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **Android / Orbital / Wayland / Windows / X11:** Unimplemented, returns `(0, 0, 0, 0)`.
+    /// - **WinRT:** On Xbox, this is the TV-safe area once the window extends beyond the visible
+    ///   bounds, see `WindowExtWinRt::set_extend_beyond_visible_bounds`.
     ///
     /// ## Example
     ///
@@ -828,6 +834,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **iOS / Android / Orbital:** Unsupported.
+    /// - **WinRT:** The view is resized back when it grows larger, which the system may refuse,
+    ///   e.g. in tablet mode.
     fn set_max_surface_size(&self, max_size: Option<Size>);
 
     /// Returns surface resize increments if any were set.
@@ -855,6 +863,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **iOS / Android:** Unsupported.
+    /// - **WinRT:** The title is shown next to the application's display name.
     fn set_title(&self, title: &str);
 
     /// Change the window transparency state.
@@ -916,6 +925,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// This only has an effect on desktop platforms.
     ///
     /// - **X11:** Due to a bug in XFCE, this has no effect on Xfwm.
+    /// - **WinRT:** The view is kept at its current size by resizing it back, the border can
+    ///   still be dragged.
     /// - **iOS / Android / Web:** Unsupported.
     ///
     /// [`WindowEvent::SurfaceResized`]: crate::event::WindowEvent::SurfaceResized
@@ -926,6 +937,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **X11:** Not implemented.
+    /// - **WinRT:** `false` in tablet mode and full screen, where views can't be resized.
     /// - **iOS / Android / Web:** Unsupported.
     fn is_resizable(&self) -> bool;
 
@@ -965,6 +977,10 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// - **Wayland**: always `None`.
     /// - **iOS / Android / Web / Orbital:** Unsupported.
+    /// - **WinRT:** `true` while the `CoreWindow` is hidden and not activated. Minimizing is
+    ///   reported as [`WindowEvent::Occluded`].
+    ///
+    /// [`WindowEvent::Occluded`]: crate::event::WindowEvent::Occluded
     fn is_minimized(&self) -> Option<bool>;
 
     /// Sets the window to maximized or back.
@@ -1024,6 +1040,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **iOS / Android / Web:** No effect.
+    /// - **WinRT:** Extends the view into the title bar, the caption buttons remain visible.
     fn set_decorations(&self, decorations: bool);
 
     /// Gets the window's current decorations state.
@@ -1233,6 +1250,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **iOS / Android / Wayland / Orbital:** Unsupported.
+    /// - **WinRT:** The system may refuse to bring the view forward while another application is
+    ///   in the foreground.
     fn focus_window(&self);
 
     /// Gets whether the window has keyboard focus.
@@ -1255,6 +1274,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// - **macOS:** `None` has no effect.
     /// - **X11:** Requests for user attention must be manually cleared.
     /// - **Wayland:** Requires `xdg_activation_v1` protocol, `None` has no effect.
+    /// - **WinRT:** Shows the attention badge on the application's tile, `Critical` also shows a
+    ///   toast. Both are cleared when the window is focused.
     fn request_user_attention(&self, request_type: Option<UserAttentionType>);
 
     /// Set or override the window theme.
@@ -1267,6 +1288,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///   get the system preference.
     /// - **X11:** Sets `_GTK_THEME_VARIANT` hint to `dark` or `light` and if `None` is used, it
     ///   will default to  [`Theme::Dark`].
+    /// - **WinRT:** Sets the title bar colors, the application content is not affected.
     /// - **iOS / Android / Web / Orbital:** Unsupported.
     fn set_theme(&self, theme: Option<Theme>);
 
@@ -1553,12 +1575,14 @@ pub enum UserAttentionType {
     /// - **macOS:** Bounces the dock icon until the application is in focus.
     /// - **Windows:** Flashes both the window and the taskbar button until the application is in
     ///   focus.
+    /// - **WinRT:** Shows the attention badge and a toast until the application is in focus.
     Critical,
 
     /// ## Platform-specific
     ///
     /// - **macOS:** Bounces the dock icon once.
     /// - **Windows:** Flashes the taskbar button until the application is in focus.
+    /// - **WinRT:** Shows the attention badge until the application is in focus.
     #[default]
     Informational,
 }
@@ -1580,6 +1604,8 @@ bitflags::bitflags! {
 /// ## Platform-specific
 ///
 /// - **iOS / Android / Web / Wayland:** Unsupported.
+/// - **WinRT:** `AlwaysOnTop` switches the view to compact overlay mode when supported,
+///   `AlwaysOnBottom` is unsupported.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WindowLevel {
@@ -0,0 +1,470 @@
+//! Running without `CoreApplication`, on a `DispatcherQueue` and the `HWND` of a Windows App SDK
+//! `AppWindow`, see `EventLoopBuilderExtWinRt::with_dispatcher_queue`.
+//!
+//! The `windows` crate doesn't project `Microsoft.UI`, so the application passes the `HWND` of its
+//! `AppWindow` instead, which winit subclasses to receive the input, size, DPI, focus and close
+//! messages of the window. The event loop runs the message loop of the thread, which also runs the
+//! `DispatcherQueue`.
+
+use std::num::NonZeroIsize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dpi::{PhysicalPosition, PhysicalSize};
+use tracing::warn;
+use windows::core::{Result as WinResult, HSTRING};
+use windows::System::{DispatcherQueue, DispatcherQueueController, VirtualKey};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::System::WinRT::{
+    CreateDispatcherQueueController, DispatcherQueueOptions, DQTAT_COM_NONE, DQTYPE_THREAD_CURRENT,
+};
+use windows::Win32::UI::Controls::WM_MOUSELEAVE;
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    ReleaseCapture, SetCapture, TrackMouseEvent, TME_LEAVE, TRACKMOUSEEVENT,
+};
+use windows::Win32::UI::Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW, GetClientRect, GetWindowLongW, GetWindowRect, GetWindowTextLengthW,
+    GetWindowTextW, MsgWaitForMultipleObjectsEx, PeekMessageW, SetWindowPos, SetWindowTextW,
+    TranslateMessage, GWL_STYLE, MSG, MWMO_INPUTAVAILABLE, PM_REMOVE, QS_ALLINPUT, SIZE_MINIMIZED,
+    SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOZORDER, USER_DEFAULT_SCREEN_DPI, WM_CHAR, WM_CLOSE,
+    WM_DESTROY, WM_DPICHANGED, WM_KEYDOWN, WM_KEYUP, WM_KILLFOCUS, WM_LBUTTONDOWN, WM_LBUTTONUP,
+    WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_QUIT,
+    WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SETFOCUS, WM_SIZE, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_XBUTTONDOWN,
+    WM_XBUTTONUP, WS_THICKFRAME, XBUTTON1,
+};
+use windows::UI::Core::CorePhysicalKeyStatus;
+use winit_core::event::{
+    ButtonSource, ElementState, MouseButton, PointerKind, PointerSource, WindowEvent,
+};
+use winit_core::window::WindowId;
+
+use crate::event_loop::{mouse_device_id, Runner};
+use crate::util::current_timestamp;
+
+/// Identifies the subclass winit installs, together with `subclass_proc`.
+const SUBCLASS_ID: usize = 0;
+
+/// Returns the `DispatcherQueue` of the calling thread, creating it if the application didn't.
+///
+/// The controller is returned if the queue was created, it must be kept while the queue is used.
+pub(crate) fn dispatcher_queue_for_current_thread(
+) -> WinResult<(DispatcherQueue, Option<DispatcherQueueController>)> {
+    if let Ok(queue) = DispatcherQueue::GetForCurrentThread() {
+        return Ok((queue, None));
+    }
+    let options = DispatcherQueueOptions {
+        dwSize: std::mem::size_of::<DispatcherQueueOptions>() as u32,
+        threadType: DQTYPE_THREAD_CURRENT,
+        // The apartment was already chosen by `with_apartment`.
+        apartmentType: DQTAT_COM_NONE,
+    };
+    let controller = unsafe { CreateDispatcherQueueController(options) }?;
+    Ok((controller.DispatcherQueue()?, Some(controller)))
+}
+
+/// Waits until a message arrives or `wait` elapsed.
+pub(crate) fn wait_for_messages(wait: Option<Duration>) {
+    let timeout = match wait {
+        // Rounded up, so that the wait doesn't end just before the deadline.
+        Some(wait) => wait.as_micros().div_ceil(1000).min(u32::MAX as u128 - 1) as u32,
+        // `INFINITE`.
+        None => u32::MAX,
+    };
+    unsafe { MsgWaitForMultipleObjectsEx(None, timeout, QS_ALLINPUT, MWMO_INPUTAVAILABLE) };
+}
+
+/// Dispatches the pending messages of the thread, returning `false` once `WM_QUIT` was received.
+pub(crate) fn dispatch_messages() -> bool {
+    let mut msg = MSG::default();
+    while unsafe { PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE) }.as_bool() {
+        if msg.message == WM_QUIT {
+            return false;
+        }
+        unsafe {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    true
+}
+
+/// The `HWND` of an `AppWindow` hosting a window, with the subclass winit installed on it.
+pub(crate) struct AppWindowHost {
+    hwnd: NonZeroIsize,
+    data: Arc<SubclassData>,
+}
+
+/// The state of the subclass, passed to `subclass_proc` as its reference data.
+///
+/// The subclass owns a strong reference, which is only released once the subclass was removed.
+struct SubclassData {
+    runner: Arc<Runner>,
+    window_id: WindowId,
+    /// Set once the window was dropped, the subclass then only forwards the messages.
+    detached: AtomicBool,
+    /// Whether the mouse is over the window, which is when `WM_MOUSELEAVE` was requested.
+    hovered: AtomicBool,
+    /// The mouse buttons held, the mouse is captured while any is.
+    buttons: AtomicU32,
+}
+
+impl AppWindowHost {
+    /// Makes the window report the surface size, the scale factor, the focus and the input of
+    /// the `AppWindow`.
+    ///
+    /// This must be called on the thread of the `AppWindow`.
+    pub(crate) fn attach(runner: &Arc<Runner>, hwnd: NonZeroIsize) -> WinResult<Self> {
+        let handle = HWND(hwnd.get());
+        let window_id = WindowId::from_raw(hwnd.get() as usize);
+
+        let mut rect = RECT::default();
+        unsafe { GetClientRect(handle, &mut rect) }?;
+        let surface_size =
+            PhysicalSize::new((rect.right - rect.left) as u32, (rect.bottom - rect.top) as u32);
+        runner.set_app_window(
+            window_id,
+            surface_size,
+            scale_factor(unsafe { GetDpiForWindow(handle) }),
+        );
+
+        let data = Arc::new(SubclassData {
+            runner: Arc::clone(runner),
+            window_id,
+            detached: AtomicBool::new(false),
+            hovered: AtomicBool::new(false),
+            buttons: AtomicU32::new(0),
+        });
+        let reference = Arc::into_raw(Arc::clone(&data));
+        if !unsafe {
+            SetWindowSubclass(handle, Some(subclass_proc), SUBCLASS_ID, reference as usize)
+        }
+        .as_bool()
+        {
+            let err = windows::core::Error::from_win32();
+            drop(unsafe { Arc::from_raw(reference) });
+            return Err(err);
+        }
+        Ok(Self { hwnd, data })
+    }
+
+    pub(crate) fn hwnd(&self) -> NonZeroIsize {
+        self.hwnd
+    }
+
+    pub(crate) fn title(&self) -> String {
+        let hwnd = HWND(self.hwnd.get());
+        let len = unsafe { GetWindowTextLengthW(hwnd) }.max(0) as usize;
+        let mut title = vec![0; len + 1];
+        let len = unsafe { GetWindowTextW(hwnd, &mut title) }.max(0) as usize;
+        String::from_utf16_lossy(&title[..len])
+    }
+
+    pub(crate) fn set_title(&self, title: &str) {
+        if let Err(err) = unsafe { SetWindowTextW(HWND(self.hwnd.get()), &HSTRING::from(title)) } {
+            warn!("Failed to set the title: {err}");
+        }
+    }
+
+    /// Whether the window has a sizing border.
+    pub(crate) fn is_resizable(&self) -> bool {
+        let style = unsafe { GetWindowLongW(HWND(self.hwnd.get()), GWL_STYLE) } as u32;
+        style & WS_THICKFRAME.0 != 0
+    }
+
+    /// Stops reporting the messages of the window, from any thread.
+    pub(crate) fn stop(&self) {
+        self.data.detached.store(true, Ordering::SeqCst);
+    }
+
+    /// Removes the subclass from the window.
+    ///
+    /// This must be called on the thread of the `AppWindow`, `RemoveWindowSubclass` fails on other
+    /// threads.
+    pub(crate) fn detach(self) {
+        self.stop();
+        let removed = unsafe {
+            RemoveWindowSubclass(HWND(self.hwnd.get()), Some(subclass_proc), SUBCLASS_ID)
+        };
+        if removed.as_bool() {
+            // Release the reference of the subclass.
+            drop(unsafe { Arc::from_raw(Arc::as_ptr(&self.data)) });
+        } else {
+            // The window may still call `subclass_proc`, which keeps the data alive.
+            warn!("failed to remove the subclass of the AppWindow");
+        }
+    }
+}
+
+/// Resizes the client area of the window to `size`, keeping its position.
+pub(crate) fn resize_client_area(hwnd: NonZeroIsize, size: PhysicalSize<u32>) {
+    let hwnd = HWND(hwnd.get());
+    let mut window = RECT::default();
+    let mut client = RECT::default();
+    if let Err(err) =
+        unsafe { GetWindowRect(hwnd, &mut window).and(GetClientRect(hwnd, &mut client)) }
+    {
+        warn!("Failed to read the size of the AppWindow: {err}");
+        return;
+    }
+    // The frame keeps its size, the client rectangle starts at the origin.
+    let width = window.right - window.left - client.right + size.width as i32;
+    let height = window.bottom - window.top - client.bottom + size.height as i32;
+    let flags = SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE;
+    if let Err(err) = unsafe { SetWindowPos(hwnd, HWND::default(), 0, 0, width, height, flags) } {
+        warn!("Failed to resize the AppWindow: {err}");
+    }
+}
+
+fn scale_factor(dpi: u32) -> f64 {
+    if dpi == 0 {
+        1.0
+    } else {
+        dpi as f64 / USER_DEFAULT_SCREEN_DPI as f64
+    }
+}
+
+unsafe extern "system" fn subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    data: usize,
+) -> LRESULT {
+    let data = unsafe { &*(data as *const SubclassData) };
+    if data.detached.load(Ordering::SeqCst) {
+        return unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) };
+    }
+    let handled = data.runner.catch_panic(|| Ok(handle_message(data, hwnd, msg, wparam, lparam)));
+    match handled {
+        Ok(Some(result)) => result,
+        _ => unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) },
+    }
+}
+
+/// Translates a message of the window, returning its result if the window procedure of the
+/// `AppWindow` must not see it.
+fn handle_message(
+    data: &SubclassData,
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> Option<LRESULT> {
+    let runner = &data.runner;
+    let window_id = data.window_id;
+    let device_id = Some(mouse_device_id());
+    match msg {
+        WM_SIZE if wparam.0 as u32 != SIZE_MINIMIZED => {
+            let size = PhysicalSize::new(low_word(lparam.0) as u32, high_word(lparam.0) as u32);
+            runner.update_surface_size(window_id, size);
+        },
+        WM_DPICHANGED => {
+            runner.update_scale_factor(window_id, scale_factor(high_word(wparam.0 as isize) as u32))
+        },
+        WM_SETFOCUS => runner.handle_focus_changed(window_id, true),
+        WM_KILLFOCUS => runner.handle_focus_changed(window_id, false),
+        WM_CLOSE => {
+            // Like on the other backends, the application decides whether to close the window.
+            runner.queue_window_event(window_id, WindowEvent::CloseRequested);
+            return Some(LRESULT(0));
+        },
+        WM_DESTROY => runner.report_window_closed(window_id),
+        WM_MOUSEMOVE => {
+            let position = client_position(lparam);
+            let timestamp = current_timestamp();
+            if !data.hovered.swap(true, Ordering::Relaxed) {
+                let mut track = TRACKMOUSEEVENT {
+                    cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                    dwFlags: TME_LEAVE,
+                    hwndTrack: hwnd,
+                    dwHoverTime: 0,
+                };
+                let _ = unsafe { TrackMouseEvent(&mut track) };
+                runner.queue_input_event(
+                    window_id,
+                    WindowEvent::PointerEntered {
+                        device_id,
+                        position,
+                        primary: true,
+                        kind: PointerKind::Mouse,
+                    },
+                    timestamp,
+                );
+            }
+            runner.queue_input_event(
+                window_id,
+                WindowEvent::PointerMoved {
+                    device_id,
+                    position,
+                    primary: true,
+                    source: PointerSource::Mouse,
+                },
+                timestamp,
+            );
+        },
+        WM_MOUSELEAVE => {
+            data.hovered.store(false, Ordering::Relaxed);
+            runner.queue_input_event(
+                window_id,
+                WindowEvent::PointerLeft {
+                    device_id,
+                    position: None,
+                    primary: true,
+                    kind: PointerKind::Mouse,
+                },
+                current_timestamp(),
+            );
+        },
+        WM_LBUTTONDOWN | WM_LBUTTONUP | WM_RBUTTONDOWN | WM_RBUTTONUP | WM_MBUTTONDOWN
+        | WM_MBUTTONUP | WM_XBUTTONDOWN | WM_XBUTTONUP => {
+            let (button, state) = mouse_button(msg, wparam);
+            let bit = 1 << button_index(button);
+            let held = match state {
+                ElementState::Pressed => data.buttons.fetch_or(bit, Ordering::Relaxed) | bit,
+                ElementState::Released => data.buttons.fetch_and(!bit, Ordering::Relaxed) & !bit,
+            };
+            // Keep receiving the mouse while a button is held outside of the window.
+            unsafe {
+                if held == 0 {
+                    let _ = ReleaseCapture();
+                } else {
+                    SetCapture(hwnd);
+                }
+            }
+            runner.queue_input_event(
+                window_id,
+                WindowEvent::PointerButton {
+                    device_id,
+                    state,
+                    position: client_position(lparam),
+                    primary: true,
+                    button: ButtonSource::Mouse(button),
+                },
+                current_timestamp(),
+            );
+            if msg == WM_XBUTTONDOWN || msg == WM_XBUTTONUP {
+                // `WM_XBUTTON*` must return `TRUE` when processed.
+                return Some(LRESULT(1));
+            }
+        },
+        WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+            let delta = high_word(wparam.0 as isize) as i16 as i32;
+            runner.queue_wheel(
+                window_id,
+                device_id,
+                delta,
+                msg == WM_MOUSEHWHEEL,
+                current_timestamp(),
+            );
+        },
+        WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => {
+            let state = if msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN {
+                ElementState::Pressed
+            } else {
+                ElementState::Released
+            };
+            runner.handle_key(
+                window_id,
+                None,
+                VirtualKey(wparam.0 as i32),
+                key_status(lparam),
+                state,
+            );
+        },
+        WM_CHAR => runner.handle_character(window_id, wparam.0 as u32),
+        _ => {},
+    }
+    None
+}
+
+fn low_word(value: isize) -> u16 {
+    value as u16
+}
+
+fn high_word(value: isize) -> u16 {
+    (value >> 16) as u16
+}
+
+/// The position of a mouse message, in physical pixels of the client area.
+fn client_position(lparam: LPARAM) -> PhysicalPosition<f64> {
+    // The coordinates are signed, they are negative left of and above a captured window.
+    let x = low_word(lparam.0) as i16;
+    let y = high_word(lparam.0) as i16;
+    PhysicalPosition::new(x as f64, y as f64)
+}
+
+fn mouse_button(msg: u32, wparam: WPARAM) -> (MouseButton, ElementState) {
+    let button = match msg {
+        WM_LBUTTONDOWN | WM_LBUTTONUP => MouseButton::Left,
+        WM_RBUTTONDOWN | WM_RBUTTONUP => MouseButton::Right,
+        WM_MBUTTONDOWN | WM_MBUTTONUP => MouseButton::Middle,
+        _ if high_word(wparam.0 as isize) == XBUTTON1 => MouseButton::Back,
+        _ => MouseButton::Forward,
+    };
+    let state = match msg {
+        WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN | WM_XBUTTONDOWN => ElementState::Pressed,
+        _ => ElementState::Released,
+    };
+    (button, state)
+}
+
+fn button_index(button: MouseButton) -> u32 {
+    match button {
+        MouseButton::Left => 0,
+        MouseButton::Right => 1,
+        MouseButton::Middle => 2,
+        MouseButton::Back => 3,
+        _ => 4,
+    }
+}
+
+/// Reads the key flags of a keyboard message, as `CoreWindow` reports them.
+fn key_status(lparam: LPARAM) -> CorePhysicalKeyStatus {
+    let flags = lparam.0 as u32;
+    CorePhysicalKeyStatus {
+        RepeatCount: flags & 0xffff,
+        ScanCode: (flags >> 16) & 0xff,
+        IsExtendedKey: flags & (1 << 24) != 0,
+        IsMenuKeyDown: flags & (1 << 29) != 0,
+        WasKeyDown: flags & (1 << 30) != 0,
+        IsKeyReleased: flags & (1 << 31) != 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_status_from_lparam() {
+        // Right Alt released: extended, context code set, previously down.
+        let status = key_status(LPARAM(0xe138_0001_u32 as i32 as isize));
+        assert_eq!(status.RepeatCount, 1);
+        assert_eq!(status.ScanCode, 0x38);
+        assert!(status.IsExtendedKey);
+        assert!(status.IsMenuKeyDown);
+        assert!(status.WasKeyDown);
+        assert!(status.IsKeyReleased);
+    }
+
+    #[test]
+    fn client_position_is_signed() {
+        let lparam = LPARAM(((-5i16 as u16 as isize) << 16) | (-3i16 as u16 as isize));
+        assert_eq!(client_position(lparam), PhysicalPosition::new(-3.0, -5.0));
+    }
+
+    #[test]
+    fn x_buttons() {
+        let back = WPARAM((XBUTTON1 as usize) << 16);
+        assert_eq!(mouse_button(WM_XBUTTONDOWN, back), (MouseButton::Back, ElementState::Pressed));
+        let forward = WPARAM(2 << 16);
+        assert_eq!(
+            mouse_button(WM_XBUTTONUP, forward),
+            (MouseButton::Forward, ElementState::Released)
+        );
+    }
+}
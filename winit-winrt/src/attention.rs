@@ -0,0 +1,43 @@
+use windows::core::{Result as WinResult, HSTRING};
+use windows::UI::Notifications::{
+    BadgeNotification, BadgeTemplateType, BadgeUpdateManager, ToastNotification,
+    ToastNotificationManager, ToastTemplateType,
+};
+
+use crate::util::ensure_winrt_initialized;
+
+/// Shows the attention glyph on the application's tile and taskbar button.
+pub(crate) fn show_attention_badge() -> WinResult<()> {
+    ensure_winrt_initialized();
+    let content = BadgeUpdateManager::GetTemplateContent(BadgeTemplateType::BadgeGlyph)?;
+    content
+        .DocumentElement()?
+        .SetAttribute(&HSTRING::from("value"), &HSTRING::from("attention"))?;
+    let badge = BadgeNotification::CreateBadgeNotification(&content)?;
+    BadgeUpdateManager::CreateBadgeUpdaterForApplication()?.Update(&badge)
+}
+
+pub(crate) fn clear_badge() {
+    ensure_winrt_initialized();
+    if let Ok(updater) = BadgeUpdateManager::CreateBadgeUpdaterForApplication() {
+        let _ = updater.Clear();
+    }
+}
+
+/// Shows a toast with a single line of text.
+pub(crate) fn show_toast(text: &str) -> WinResult<ToastNotification> {
+    ensure_winrt_initialized();
+    let content = ToastNotificationManager::GetTemplateContent(ToastTemplateType::ToastText01)?;
+    let text_node = content.CreateTextNode(&HSTRING::from(text))?;
+    content.GetElementsByTagName(&HSTRING::from("text"))?.Item(0)?.AppendChild(&text_node)?;
+    let toast = ToastNotification::CreateToastNotification(&content)?;
+    ToastNotificationManager::CreateToastNotifier()?.Show(&toast)?;
+    Ok(toast)
+}
+
+pub(crate) fn hide_toast(toast: &ToastNotification) {
+    ensure_winrt_initialized();
+    if let Ok(notifier) = ToastNotificationManager::CreateToastNotifier() {
+        let _ = notifier.Hide(toast);
+    }
+}
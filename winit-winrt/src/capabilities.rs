@@ -0,0 +1,54 @@
+use windows::Devices::Input::{
+    MouseCapabilities as WinRtMouseCapabilities, TouchCapabilities as WinRtTouchCapabilities,
+};
+
+use crate::util::ensure_winrt_initialized;
+
+/// Mouse capabilities of the device, as reported by `Windows.Devices.Input.MouseCapabilities`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseCapabilities {
+    /// Whether a mouse (or other relative pointing device) is connected.
+    pub mouse_present: bool,
+    /// Whether any connected mouse has a vertical wheel.
+    pub vertical_wheel_present: bool,
+    /// Whether any connected mouse has a horizontal wheel.
+    pub horizontal_wheel_present: bool,
+    /// Whether the user has swapped the primary and secondary mouse buttons.
+    pub swap_buttons: bool,
+    /// The number of buttons on the mouse with the most buttons.
+    pub number_of_buttons: u32,
+}
+
+/// Touch capabilities of the device, as reported by `Windows.Devices.Input.TouchCapabilities`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TouchCapabilities {
+    /// Whether a touch digitizer is present.
+    pub touch_present: bool,
+    /// The minimum number of contacts supported by all digitizers.
+    pub contacts: u32,
+}
+
+pub(crate) fn mouse_capabilities() -> MouseCapabilities {
+    ensure_winrt_initialized();
+    let Ok(caps) = WinRtMouseCapabilities::new() else {
+        return MouseCapabilities::default();
+    };
+    MouseCapabilities {
+        mouse_present: caps.MousePresent().unwrap_or(0) != 0,
+        vertical_wheel_present: caps.VerticalWheelPresent().unwrap_or(0) != 0,
+        horizontal_wheel_present: caps.HorizontalWheelPresent().unwrap_or(0) != 0,
+        swap_buttons: caps.SwapButtons().unwrap_or(0) != 0,
+        number_of_buttons: caps.NumberOfButtons().unwrap_or(0),
+    }
+}
+
+pub(crate) fn touch_capabilities() -> TouchCapabilities {
+    ensure_winrt_initialized();
+    let Ok(caps) = WinRtTouchCapabilities::new() else {
+        return TouchCapabilities::default();
+    };
+    TouchCapabilities {
+        touch_present: caps.TouchPresent().unwrap_or(0) != 0,
+        contacts: caps.Contacts().unwrap_or(0),
+    }
+}
@@ -0,0 +1,27 @@
+use windows::core::HSTRING;
+use windows::ApplicationModel::DataTransfer::{Clipboard, DataPackage, StandardDataFormats};
+
+use crate::util::ensure_winrt_initialized;
+
+/// Returns the text on the clipboard, or `None` if it holds no text.
+pub(crate) fn clipboard_text() -> Option<String> {
+    ensure_winrt_initialized();
+    let content = Clipboard::GetContent().ok()?;
+    if !content.Contains(&StandardDataFormats::Text().ok()?).ok()? {
+        return None;
+    }
+    Some(content.GetTextAsync().ok()?.get().ok()?.to_string_lossy())
+}
+
+/// Replaces the content of the clipboard with `text`.
+pub(crate) fn set_clipboard_text(text: &str) -> bool {
+    ensure_winrt_initialized();
+    let set = || {
+        let package = DataPackage::new()?;
+        package.SetText(&HSTRING::from(text))?;
+        Clipboard::SetContent(&package)?;
+        // Keep the text available after the application exits.
+        Clipboard::Flush()
+    };
+    set().is_ok()
+}
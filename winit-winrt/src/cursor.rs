@@ -1,5 +1,17 @@
 use windows::UI::Core::CoreCursorType;
-use winit_core::cursor::CursorIcon;
+use winit_core::cursor::{CursorIcon, CustomCursorProvider};
+
+/// A custom cursor backed by a cursor resource in the application package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct WinRtCustomCursor {
+    pub(crate) resource_id: u32,
+}
+
+impl CustomCursorProvider for WinRtCustomCursor {
+    fn is_animated(&self) -> bool {
+        false
+    }
+}
 
 pub fn cursor_icon_to_core(icon: CursorIcon) -> CoreCursorType {
     match icon {
@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use dpi::{LogicalPosition, PhysicalPosition};
+use windows::core::{implement, Interface, Result as WinResult, HRESULT, HSTRING};
+use windows::ApplicationModel::DataTransfer::DragDrop::Core::{
+    CoreDragDropManager, CoreDragInfo, CoreDragOperation, CoreDragUIOverride,
+    CoreDropOperationTargetRequestedEventArgs, ICoreDropOperationTarget,
+    ICoreDropOperationTarget_Impl,
+};
+use windows::ApplicationModel::DataTransfer::{DataPackageOperation, StandardDataFormats};
+use windows::Foundation::Collections::IIterable;
+use windows::Foundation::{
+    AsyncActionCompletedHandler, AsyncOperationCompletedHandler, AsyncStatus, IAsyncAction,
+    IAsyncAction_Impl, IAsyncInfo, IAsyncInfo_Impl, IAsyncOperation, IAsyncOperation_Impl,
+    TypedEventHandler,
+};
+use windows::Storage::{IStorageItem, StorageFile, StorageFolder};
+use winit_core::event::WindowEvent;
+use winit_core::window::WindowId;
+
+use crate::event_loop::Runner;
+use crate::util::ensure_winrt_initialized;
+use crate::DragData;
+
+const E_NOTIMPL: HRESULT = HRESULT(0x8000_4001_u32 as i32);
+
+/// Makes the window a drop target for files, reported as `WindowEvent::Drag*` events.
+pub(crate) fn register_drop_target(runner: &Arc<Runner>, window_id: WindowId) {
+    let Ok(manager) = CoreDragDropManager::GetForCurrentView() else {
+        return;
+    };
+    let runner = Arc::clone(runner);
+    let _ = manager.TargetRequested(&TypedEventHandler::<
+        CoreDragDropManager,
+        CoreDropOperationTargetRequestedEventArgs,
+    >::new(move |_, args| {
+        runner.catch_panic(|| {
+            if let Some(args) = args {
+                let target = DropTarget {
+                    runner: Arc::clone(&runner),
+                    window_id,
+                    paths: Mutex::new(Vec::new()),
+                };
+                args.SetTarget(&ICoreDropOperationTarget::from(target))?;
+            }
+            Ok(())
+        })
+    }));
+}
+
+/// Starts dragging `data` out of the window with the given pressed pointer.
+pub(crate) fn start_drag(pointer_id: u32, data: DragData) -> bool {
+    ensure_winrt_initialized();
+    let start = || -> WinResult<()> {
+        let operation = CoreDragOperation::new()?;
+        operation.SetPointerId(pointer_id)?;
+        let package = operation.Data()?;
+        match data {
+            DragData::Text(text) => package.SetText(&HSTRING::from(text))?,
+            DragData::Paths(paths) => {
+                let items =
+                    paths.iter().map(|path| storage_item(path)).collect::<WinResult<Vec<_>>>()?;
+                package.SetStorageItemsReadOnly(&IIterable::<IStorageItem>::try_from(items)?)?;
+            },
+        }
+        operation.SetAllowedOperations(DataPackageOperation::Copy)?;
+        // The operation completes when the item is dropped, there is nothing to wait for.
+        operation.StartAsync()?;
+        Ok(())
+    };
+    start().is_ok()
+}
+
+fn storage_item(path: &Path) -> WinResult<Option<IStorageItem>> {
+    let item = if path.is_dir() {
+        StorageFolder::GetFolderFromPathAsync(&HSTRING::from(path))?.get()?.cast()?
+    } else {
+        StorageFile::GetFileFromPathAsync(&HSTRING::from(path))?.get()?.cast()?
+    };
+    Ok(Some(item))
+}
+
+/// Returns the paths of the files being dragged, skipping items without one.
+fn storage_item_paths(info: &CoreDragInfo) -> Vec<PathBuf> {
+    let paths = || -> WinResult<Vec<PathBuf>> {
+        let data = info.Data()?;
+        if !data.Contains(&StandardDataFormats::StorageItems()?)? {
+            return Ok(Vec::new());
+        }
+        let items = data.GetStorageItemsAsync()?.get()?;
+        Ok(items
+            .into_iter()
+            .filter_map(|item| item.Path().ok())
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(path.to_os_string()))
+            .collect())
+    };
+    paths().unwrap_or_default()
+}
+
+#[implement(ICoreDropOperationTarget)]
+struct DropTarget {
+    runner: Arc<Runner>,
+    window_id: WindowId,
+    /// The paths of the dragged files, resolved when the drag enters the window.
+    paths: Mutex<Vec<PathBuf>>,
+}
+
+impl DropTarget {
+    fn position(&self, info: &CoreDragInfo) -> PhysicalPosition<f64> {
+        let position = info.Position().unwrap_or_default();
+        LogicalPosition::new(position.X as f64, position.Y as f64)
+            .to_physical(self.runner.scale_factor(self.window_id))
+    }
+
+    /// Accepts the drag as a copy if it carries files, otherwise the drop is refused.
+    fn operation(&self) -> IAsyncOperation<DataPackageOperation> {
+        let operation = if self.paths.lock().unwrap().is_empty() {
+            DataPackageOperation::None
+        } else {
+            DataPackageOperation::Copy
+        };
+        CompletedOperation(operation).into()
+    }
+}
+
+impl ICoreDropOperationTarget_Impl for DropTarget {
+    fn EnterAsync(
+        &self,
+        info: Option<&CoreDragInfo>,
+        _ui_override: Option<&CoreDragUIOverride>,
+    ) -> WinResult<IAsyncOperation<DataPackageOperation>> {
+        self.runner.catch_panic(|| {
+            if let Some(info) = info {
+                let paths = storage_item_paths(info);
+                *self.paths.lock().unwrap() = paths.clone();
+                if !paths.is_empty() {
+                    let position = self.position(info);
+                    self.runner.queue_window_event(
+                        self.window_id,
+                        WindowEvent::DragEntered { paths, position },
+                    );
+                }
+            }
+            Ok(self.operation())
+        })
+    }
+
+    fn OverAsync(
+        &self,
+        info: Option<&CoreDragInfo>,
+        _ui_override: Option<&CoreDragUIOverride>,
+    ) -> WinResult<IAsyncOperation<DataPackageOperation>> {
+        self.runner.catch_panic(|| {
+            if let Some(info) = info.filter(|_| !self.paths.lock().unwrap().is_empty()) {
+                let position = self.position(info);
+                self.runner.queue_window_event(self.window_id, WindowEvent::DragMoved { position });
+            }
+            Ok(self.operation())
+        })
+    }
+
+    fn LeaveAsync(&self, info: Option<&CoreDragInfo>) -> WinResult<IAsyncAction> {
+        self.runner.catch_panic(|| {
+            if !std::mem::take(&mut *self.paths.lock().unwrap()).is_empty() {
+                let position = info.map(|info| self.position(info));
+                self.runner.queue_window_event(self.window_id, WindowEvent::DragLeft { position });
+            }
+            Ok(CompletedAction.into())
+        })
+    }
+
+    fn DropAsync(
+        &self,
+        info: Option<&CoreDragInfo>,
+    ) -> WinResult<IAsyncOperation<DataPackageOperation>> {
+        self.runner.catch_panic(|| {
+            let operation = self.operation();
+            let paths = std::mem::take(&mut *self.paths.lock().unwrap());
+            if let Some(info) = info.filter(|_| !paths.is_empty()) {
+                let position = self.position(info);
+                self.runner.queue_window_event(
+                    self.window_id,
+                    WindowEvent::DragDropped { paths, position },
+                );
+            }
+            Ok(operation)
+        })
+    }
+}
+
+/// `IAsyncOperation` which has already completed, WinRT has no built-in way to create one.
+#[implement(IAsyncOperation<DataPackageOperation>, IAsyncInfo)]
+struct CompletedOperation(DataPackageOperation);
+
+/// `IAsyncAction` which has already completed.
+#[implement(IAsyncAction, IAsyncInfo)]
+struct CompletedAction;
+
+macro_rules! completed_async_info {
+    ($($ty:ty),*) => {
+        $(impl IAsyncInfo_Impl for $ty {
+            fn Id(&self) -> WinResult<u32> {
+                Ok(1)
+            }
+
+            fn Status(&self) -> WinResult<AsyncStatus> {
+                Ok(AsyncStatus::Completed)
+            }
+
+            fn ErrorCode(&self) -> WinResult<HRESULT> {
+                Ok(HRESULT(0))
+            }
+
+            fn Cancel(&self) -> WinResult<()> {
+                Ok(())
+            }
+
+            fn Close(&self) -> WinResult<()> {
+                Ok(())
+            }
+        })*
+    };
+}
+
+completed_async_info!(CompletedOperation, CompletedAction);
+
+impl IAsyncOperation_Impl<DataPackageOperation> for CompletedOperation {
+    fn SetCompleted(
+        &self,
+        handler: Option<&AsyncOperationCompletedHandler<DataPackageOperation>>,
+    ) -> WinResult<()> {
+        // The operation has already completed, so the handler is invoked right away.
+        if let Some(handler) = handler {
+            // SAFETY: `self` was allocated by `#[implement]`.
+            let this = unsafe { self.cast::<IAsyncOperation<DataPackageOperation>>()? };
+            handler.Invoke(&this, AsyncStatus::Completed)?;
+        }
+        Ok(())
+    }
+
+    fn Completed(&self) -> WinResult<AsyncOperationCompletedHandler<DataPackageOperation>> {
+        Err(E_NOTIMPL.into())
+    }
+
+    fn GetResults(&self) -> WinResult<DataPackageOperation> {
+        Ok(self.0)
+    }
+}
+
+impl IAsyncAction_Impl for CompletedAction {
+    fn SetCompleted(&self, handler: Option<&AsyncActionCompletedHandler>) -> WinResult<()> {
+        if let Some(handler) = handler {
+            // SAFETY: `self` was allocated by `#[implement]`.
+            let this = unsafe { self.cast::<IAsyncAction>()? };
+            handler.Invoke(&this, AsyncStatus::Completed)?;
+        }
+        Ok(())
+    }
+
+    fn Completed(&self) -> WinResult<AsyncActionCompletedHandler> {
+        Err(E_NOTIMPL.into())
+    }
+
+    fn GetResults(&self) -> WinResult<()> {
+        Ok(())
+    }
+}
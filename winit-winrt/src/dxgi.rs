@@ -0,0 +1,25 @@
+use windows::core::Result as WinResult;
+use windows::Win32::Graphics::Dxgi::{
+    CreateDXGIFactory1, IDXGIFactory1, IDXGIOutput, DXGI_FRAME_STATISTICS,
+};
+
+use crate::util::qpc_to_duration;
+use crate::FrameStatistics;
+
+/// Returns the output of the primary adapter that the view is presented on.
+pub(crate) fn primary_output() -> WinResult<IDXGIOutput> {
+    unsafe { CreateDXGIFactory1::<IDXGIFactory1>()?.EnumAdapters1(0)?.EnumOutputs(0) }
+}
+
+/// Returns the statistics of the frames recently presented on the primary output.
+pub(crate) fn frame_statistics() -> Option<FrameStatistics> {
+    let output = primary_output().ok()?;
+    let mut stats = DXGI_FRAME_STATISTICS::default();
+    unsafe { output.GetFrameStatistics(&mut stats) }.ok()?;
+    Some(FrameStatistics {
+        present_count: stats.PresentCount,
+        present_refresh_count: stats.PresentRefreshCount,
+        sync_refresh_count: stats.SyncRefreshCount,
+        sync_time: qpc_to_duration(stats.SyncQPCTime)?,
+    })
+}
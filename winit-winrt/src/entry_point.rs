@@ -0,0 +1,75 @@
+/// Generates the entry points of a WinRT application, which calls `$main` with a new
+/// `winit::event_loop::EventLoop` on the view thread.
+///
+/// UWP applications are started through `wWinMain` (or `WinMain` without `-municode`), so the
+/// crate has to be marked `#![no_main]`. Both are generated, and the Windows Runtime is
+/// initialized by the event loop as configured with [`EventLoopBuilderExtWinRt::with_apartment`].
+/// The application has to depend on `winit`.
+///
+/// An optional second argument configures the `winit::event_loop::EventLoopBuilder` before the
+/// event loop is built. `$main` is called through
+/// [`EventLoopViewExtWinRt::run_view_never_return`], and the process exits with the exit code of
+/// the event loop once it returns, or with `1` if the event loop can't be created.
+///
+/// ```ignore
+/// #![no_main]
+///
+/// use winit::event_loop::EventLoop;
+/// use winit::platform::winrt::EventLoopBuilderExtWinRt;
+///
+/// winit::platform::winrt::main!(run, |builder| {
+///     builder.with_back_requested_as_close(true);
+/// });
+///
+/// fn run(event_loop: EventLoop) {
+///     event_loop.run_app(App::default()).unwrap();
+/// }
+/// ```
+///
+/// [`EventLoopBuilderExtWinRt::with_apartment`]: crate::EventLoopBuilderExtWinRt::with_apartment
+/// [`EventLoopViewExtWinRt::run_view_never_return`]: crate::EventLoopViewExtWinRt::run_view_never_return
+#[macro_export]
+macro_rules! main {
+    ($main:path $(,)?) => {
+        $crate::main!($main, |_| {});
+    };
+    ($main:path, $configure:expr $(,)?) => {
+        fn __winit_winrt_main() -> i32 {
+            let configure: fn(&mut ::winit::event_loop::EventLoopBuilder) = $configure;
+            let mut builder = ::winit::event_loop::EventLoop::builder();
+            configure(&mut builder);
+            match builder.build() {
+                Ok(event_loop) => {
+                    $crate::EventLoopViewExtWinRt::run_view_never_return(event_loop, |event_loop| {
+                        $main(event_loop);
+                    })
+                },
+                Err(err) => {
+                    // GUI processes have no stderr, so this is reported like the other errors of winit.
+                    $crate::__tracing::error!("failed to create the event loop: {err}");
+                    1
+                },
+            }
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "system" fn wWinMain(
+            _instance: *mut ::core::ffi::c_void,
+            _prev_instance: *mut ::core::ffi::c_void,
+            _cmd_line: *mut u16,
+            _show_cmd: i32,
+        ) -> i32 {
+            __winit_winrt_main()
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "system" fn WinMain(
+            _instance: *mut ::core::ffi::c_void,
+            _prev_instance: *mut ::core::ffi::c_void,
+            _cmd_line: *mut u8,
+            _show_cmd: i32,
+        ) -> i32 {
+            __winit_winrt_main()
+        }
+    };
+}
@@ -1,31 +1,75 @@
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
-
-use dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroIsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dpi::{LogicalPosition, LogicalSize, LogicalUnit, PhysicalPosition, PhysicalSize, Size};
 use smol_str::SmolStr;
-use windows::core::{implement, AgileReference, IInspectable, Result as WinResult};
+use tracing::warn;
+use windows::core::{
+    implement, AgileReference, IInspectable, Interface, Result as WinResult, HSTRING,
+};
+use windows::ApplicationModel::Activation::{IActivatedEventArgs, LaunchActivatedEventArgs};
 use windows::ApplicationModel::Core::{
-    CoreApplication, CoreApplicationView, IFrameworkView, IFrameworkViewSource,
-    IFrameworkViewSource_Impl, IFrameworkView_Impl,
+    CoreApplication, CoreApplicationView, CoreApplicationViewTitleBar, IFrameworkView,
+    IFrameworkViewSource, IFrameworkViewSource_Impl, IFrameworkView_Impl,
+};
+use windows::ApplicationModel::DataTransfer::Clipboard;
+use windows::ApplicationModel::Resources::Core::ResourceContext;
+use windows::ApplicationModel::{
+    EnteredBackgroundEventArgs, LeavingBackgroundEventArgs, SuspendingDeferral, SuspendingEventArgs,
+};
+use windows::Devices::Input::{MouseDevice, MouseEventArgs, PenDevice, PointerDeviceType};
+use windows::Foundation::Collections::MapChangedEventHandler;
+use windows::Foundation::{
+    AsyncOperationCompletedHandler, Deferral, EventHandler, EventRegistrationToken, Rect,
+    Size as WinRtSize, TypedEventHandler,
+};
+use windows::Graphics::Display::{DisplayInformation, ResolutionScale};
+use windows::Graphics::Holographic::{HolographicCamera, HolographicSpace};
+use windows::System::Threading::{ThreadPoolTimer, TimerElapsedHandler};
+use windows::System::{
+    DispatcherQueue, DispatcherQueueController, DispatcherQueueHandler, VirtualKey,
+};
+use windows::Win32::Foundation::E_UNEXPECTED;
+use windows::UI::Composition::{CompositionTarget, Compositor};
+use windows::UI::Core::Preview::{
+    SystemNavigationCloseRequestedPreviewEventArgs, SystemNavigationManagerPreview,
 };
-use windows::Devices::Input::PointerDeviceType;
-use windows::Foundation::TypedEventHandler;
-use windows::Graphics::Display::DisplayInformation;
-use windows::System::VirtualKey;
 use windows::UI::Core::{
-    CharacterReceivedEventArgs, CoreDispatcher, CoreDispatcherPriority, CoreProcessEventsOption,
-    CoreVirtualKeyStates, CoreWindow as WinRtCoreWindow, CoreWindowActivationState,
-    CoreWindowEventArgs, KeyEventArgs, PointerEventArgs, WindowActivatedEventArgs,
-    WindowSizeChangedEventArgs,
+    AcceleratorKeyEventArgs, BackRequestedEventArgs, CharacterReceivedEventArgs,
+    CoreAcceleratorKeyEventType, CoreDispatcher, CoreDispatcherPriority, CorePhysicalKeyStatus,
+    CoreProcessEventsOption, CoreVirtualKeyStates, CoreWindow as WinRtCoreWindow,
+    CoreWindowActivationState, CoreWindowEventArgs, DispatchedHandler, KeyEventArgs,
+    PointerEventArgs, SystemNavigationManager, VisibilityChangedEventArgs,
+    WindowActivatedEventArgs, WindowSizeChangedEventArgs,
+};
+use windows::UI::Input::{
+    GestureRecognizer, GestureSettings, KeyboardDeliveryInterceptor,
+    ManipulationCompletedEventArgs, ManipulationDelta, ManipulationStartedEventArgs,
+    ManipulationUpdatedEventArgs, PointerPoint, PointerPointProperties, PointerUpdateKind,
+    TappedEventArgs,
+};
+use windows::UI::Notifications::ToastNotification;
+use windows::UI::Text::Core::CoreTextServicesManager;
+use windows::UI::ViewManagement::{
+    ApplicationView, ApplicationViewWindowingMode, InputPane, InputPaneVisibilityEventArgs,
+    UISettings, UISettingsAnimationsEnabledChangedEventArgs,
 };
-use windows::UI::Input::{PointerPointProperties, PointerUpdateKind};
+use winit_core::application::winrt::{ApplicationHandlerExtWinRt, SpatialInputEvent};
 use winit_core::application::ApplicationHandler;
 use winit_core::cursor::{CustomCursor, CustomCursorSource};
 use winit_core::error::{EventLoopError, NotSupportedError, RequestError};
 use winit_core::event::{
-    ElementState, Modifiers, MouseButton, MouseScrollDelta, StartCause, TouchPhase, WindowEvent,
+    ButtonSource, DeviceEvent, DeviceId, ElementState, Modifiers, MouseButton, MouseScrollDelta,
+    StartCause, SurfaceSizeWriter, TouchPhase, WindowEvent,
 };
+use winit_core::event_loop::pump_events::PumpStatus;
 use winit_core::event_loop::{
     ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents, EventLoopProxy as CoreProxy,
     EventLoopProxyProvider, OwnedDisplayHandle as CoreOwnedDisplayHandle,
@@ -34,16 +78,55 @@ use winit_core::keyboard::{
     Key, KeyLocation, ModifiersKeys, ModifiersState, NativeKeyCode, PhysicalKey,
 };
 use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
-use winit_core::window::{Window as CoreWindowTrait, WindowAttributes, WindowId};
+use winit_core::window::{
+    Theme, UserAttentionType, Window as CoreWindowTrait, WindowAttributes, WindowId,
+};
 
+use crate::app_window;
+use crate::attention;
+use crate::cursor::WinRtCustomCursor;
+use crate::drag_drop;
+use crate::dxgi::primary_output;
+use crate::holographic;
+use crate::independent_input;
+use crate::language::layout_is_right_to_left;
 use crate::monitor::MonitorHandle;
-use crate::util::ensure_winrt_initialized;
+use crate::theme::{system_theme, theme_from_settings};
+use crate::title_bar::core_title_bar;
+use crate::util::{
+    current_timestamp, ensure_winrt_initialized, key_state_down, rect_to_physical, set_apartment,
+    wheel_scroll_lines,
+};
 use crate::window::Window;
+use crate::{
+    Apartment, DispatcherPriority, HeadlessSurface, InputDevices, LaunchArguments,
+    LaunchWindowingMode,
+};
+
+/// The ID of the window in headless mode, where there is no `CoreWindow` to derive it from.
+const HEADLESS_WINDOW_ID: WindowId = WindowId::from_raw(0);
 
-const GLOBAL_WINDOW_ID: WindowId = WindowId::from_raw(0);
+/// Returns the ID of the window backed by the given `CoreWindow`.
+fn window_id_for(window: &WinRtCoreWindow) -> WindowId {
+    WindowId::from_raw(window.as_raw() as usize)
+}
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct PlatformSpecificEventLoopAttributes {}
+pub struct PlatformSpecificEventLoopAttributes {
+    pub coalesced_pointer_history: bool,
+    pub preferred_launch_mode: Option<LaunchWindowingMode>,
+    pub back_requested_as_close: bool,
+    pub close_confirmation: bool,
+    pub vsync_redraws: bool,
+    pub headless: Option<HeadlessSurface>,
+    pub dispatcher_priority: DispatcherPriority,
+    pub apartment: Apartment,
+    pub deferred_activation: bool,
+    pub attach_to_current_view: bool,
+    pub independent_input: InputDevices,
+    pub holographic: bool,
+    pub dispatcher_queue: bool,
+}
 
 pub struct EventLoop {
     runner: Arc<Runner>,
@@ -58,16 +141,23 @@ impl std::fmt::Debug for EventLoop {
 
 impl EventLoop {
     pub fn new(
-        _attributes: &mut PlatformSpecificEventLoopAttributes,
+        attributes: &mut PlatformSpecificEventLoopAttributes,
     ) -> Result<Self, EventLoopError> {
         static EVENT_LOOP_CREATED: AtomicBool = AtomicBool::new(false);
         if EVENT_LOOP_CREATED.swap(true, Ordering::Relaxed) {
             return Err(EventLoopError::RecreationAttempt);
         }
 
+        set_apartment(attributes.apartment);
         ensure_winrt_initialized();
 
-        let runner = Arc::new(Runner::new());
+        let runner = Arc::new(Runner::new(attributes));
+        if runner.attached {
+            runner.attach_to_current_view().map_err(|err| EventLoopError::Os(os_error!(err)))?;
+        }
+        if runner.app_sdk {
+            runner.set_dispatcher_queue().map_err(|err| EventLoopError::Os(os_error!(err)))?;
+        }
         let window_target = ActiveEventLoop { runner: Arc::clone(&runner) };
 
         Ok(Self { runner, window_target })
@@ -77,13 +167,61 @@ impl EventLoop {
         &self.window_target
     }
 
+    pub fn run_app<A: ApplicationHandler + 'static>(self, app: A) -> Result<(), EventLoopError> {
+        // The loop runs on the calling thread, which has to be the view thread unless there is no
+        // view or the host runs it. The handler can't be moved there, it is created there by the
+        // function passed to `run_view_never_return`.
+        let on_view_thread = self
+            .runner
+            .dispatcher()
+            .is_some_and(|dispatcher| dispatcher.HasThreadAccess().unwrap_or(false));
+        if !(self.runner.headless || self.runner.attached || self.runner.app_sdk || on_view_thread)
+        {
+            return Err(EventLoopError::NotSupported(NotSupportedError::new(
+                "the event loop can only run on the view thread, see \
+                 `EventLoopViewExtWinRt::run_view_never_return`",
+            )));
+        }
+
+        self.runner.run_loop(app);
+        self.runner.resume_panic();
+
+        match self.runner.exit_code.load(Ordering::SeqCst) {
+            0 => Ok(()),
+            code => Err(EventLoopError::ExitFailure(code)),
+        }
+    }
+
     pub fn run_app_never_return<A: ApplicationHandler + 'static>(self, app: A) -> ! {
-        self.runner.set_app(app);
+        let code = match self.run_app(app) {
+            Ok(()) => 0,
+            Err(EventLoopError::ExitFailure(code)) => code,
+            Err(_) => 1,
+        };
+        std::process::exit(code)
+    }
+
+    pub fn run_view_never_return<F: FnOnce(Self) + Send + 'static>(self, main: F) -> ! {
+        let runner = Arc::clone(&self.runner);
+        if runner.headless || runner.attached || runner.app_sdk {
+            main(self);
+            std::process::exit(runner.exit_code.load(Ordering::SeqCst))
+        }
+        runner.set_view_main(Box::new(move || main(self)));
 
-        let view: IFrameworkViewSource = FrameworkViewSource::new(self.runner.clone()).into();
+        let view: IFrameworkViewSource = FrameworkViewSource::new(runner.clone()).into();
         let _ = CoreApplication::Run(&view);
+        runner.resume_panic();
+
+        std::process::exit(runner.exit_code.load(Ordering::SeqCst))
+    }
 
-        std::process::exit(0)
+    pub fn pump_app_events<A: ApplicationHandler>(
+        &mut self,
+        timeout: Option<Duration>,
+        mut app: A,
+    ) -> PumpStatus {
+        self.runner.pump_events(&self.window_target, timeout, &mut app)
     }
 }
 
@@ -118,23 +256,34 @@ impl RootActiveEventLoop for ActiveEventLoop {
 
     fn create_custom_cursor(
         &self,
-        _custom_cursor: CustomCursorSource,
+        custom_cursor: CustomCursorSource,
     ) -> Result<CustomCursor, RequestError> {
-        Err(NotSupportedError::new("custom cursors are not supported on WinRT").into())
+        // UWP can only load cursors from the application package, so the source has to be
+        // mapped to a cursor resource beforehand.
+        let Some(resource_id) = self.runner.cursor_resource(&custom_cursor) else {
+            return Err(NotSupportedError::new(
+                "no cursor resource registered for this source, see \
+                 `EventLoopExtWinRt::register_cursor_resource`",
+            )
+            .into());
+        };
+        Ok(CustomCursor(Arc::new(WinRtCustomCursor { resource_id })))
     }
 
     fn available_monitors(&self) -> Box<dyn Iterator<Item = CoreMonitorHandle>> {
-        Box::new(std::iter::once(self.runner.monitor_handle().to_core()))
+        Box::new(std::iter::once(self.runner.monitor_handle(self.runner.window_id()).to_core()))
     }
 
     fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
-        Some(self.runner.monitor_handle().to_core())
+        Some(self.runner.monitor_handle(self.runner.window_id()).to_core())
     }
 
-    fn listen_device_events(&self, _allowed: DeviceEvents) {}
+    fn listen_device_events(&self, allowed: DeviceEvents) {
+        self.runner.listen_device_events(allowed);
+    }
 
-    fn system_theme(&self) -> Option<winit_core::window::Theme> {
-        None
+    fn system_theme(&self) -> Option<Theme> {
+        self.runner.system_theme()
     }
 
     fn set_control_flow(&self, control_flow: ControlFlow) {
@@ -146,8 +295,9 @@ impl RootActiveEventLoop for ActiveEventLoop {
     }
 
     fn exit(&self) {
+        // `run_loop` returns from `IFrameworkView::Run` once the current iteration is done,
+        // which ends the application after the handler was dropped.
         self.runner.exit.store(true, Ordering::SeqCst);
-        let _ = CoreApplication::Exit();
     }
 
     fn exiting(&self) -> bool {
@@ -181,53 +331,345 @@ impl EventLoopProxyProvider for EventLoopProxy {
 
 #[derive(Debug, Clone)]
 pub(crate) enum Event {
-    Window { window_id: WindowId, event: WindowEvent },
+    Window {
+        window_id: WindowId,
+        event: WindowEvent,
+        timestamp: Option<Duration>,
+    },
+    Device {
+        device_id: Option<DeviceId>,
+        event: DeviceEvent,
+    },
     WakeUp,
+    /// The scale factor of a window changed, see `Runner::update_scale_factor`.
+    ScaleFactorChanged {
+        window_id: WindowId,
+        scale_factor: f64,
+        /// The surface size the application may overwrite, kept alive until it was dispatched.
+        surface_size: Arc<Mutex<PhysicalSize<u32>>>,
+    },
+    /// The application entered the background, see `CoreApplication::EnteredBackground`.
+    Suspended(Option<Deferral>),
+    /// The application is leaving the background, see `CoreApplication::LeavingBackground`.
+    Resumed,
+    /// The application is being suspended, see `CoreApplication::Suspending`.
+    DestroySurfaces(Option<SuspendingDeferral>),
+    /// The application was resumed from suspension, see `CoreApplication::Resuming`.
+    CanCreateSurfaces,
+    /// The close button of the title bar was clicked, see
+    /// `SystemNavigationManagerPreview::CloseRequested`.
+    CloseRequested {
+        window_id: WindowId,
+        args: SystemNavigationCloseRequestedPreviewEventArgs,
+        deferral: Option<Deferral>,
+    },
+    /// A camera was added to the holographic space, see `HolographicSpace::CameraAdded`.
+    HolographicCameraAdded {
+        window_id: WindowId,
+        camera_id: u32,
+        deferral: Option<Deferral>,
+    },
+    WinRt(WinRtEvent),
+}
+
+/// Events delivered through [`ApplicationHandlerExtWinRt`].
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone)]
+pub(crate) enum WinRtEvent {
+    LayoutDirectionChanged {
+        right_to_left: bool,
+    },
+    InputPaneOcclusionChanged {
+        window_id: WindowId,
+        position: PhysicalPosition<i32>,
+        size: PhysicalSize<u32>,
+    },
+    TitleBarLayoutChanged {
+        window_id: WindowId,
+    },
+    AnimationsEnabledChanged {
+        enabled: bool,
+    },
+    AdvancedColorInfoChanged {
+        window_id: WindowId,
+        sdr_white_level: f32,
+    },
+    ColorProfileChanged {
+        window_id: WindowId,
+    },
+    SurfaceRotationChanged {
+        window_id: WindowId,
+    },
+    LaunchActivated(LaunchArguments),
+    WindowActivated {
+        window_id: WindowId,
+        pointer_activated: bool,
+    },
+    InputLanguageChanged {
+        language: String,
+    },
+    ClipboardContentChanged,
+    HolographicCameraRemoved {
+        window_id: WindowId,
+        camera_id: u32,
+    },
+    SpatialInput {
+        window_id: WindowId,
+        event: SpatialInputEvent,
+    },
+    ResizeStarted {
+        window_id: WindowId,
+    },
+    ResizeCompleted {
+        window_id: WindowId,
+    },
+}
+
+/// Text from `CharacterReceived` that hasn't been delivered yet.
+#[derive(Default)]
+struct ReceivedText {
+    window_id: Option<WindowId>,
+    text: String,
+    /// The first half of a surrogate pair, `CharacterReceived` reports UTF-16 code units.
+    high_surrogate: Option<u16>,
+}
+
+impl ReceivedText {
+    /// Appends a character code from `CharacterReceived`, returns whether the text changed.
+    fn push_code(&mut self, code: u32) -> bool {
+        let ch = match u16::try_from(code) {
+            Ok(unit @ 0xd800..=0xdbff) => {
+                self.high_surrogate = Some(unit);
+                return false;
+            },
+            Ok(unit @ 0xdc00..=0xdfff) => {
+                let Some(high) = self.high_surrogate.take() else {
+                    return false;
+                };
+                char::decode_utf16([high, unit]).next().and_then(Result::ok)
+            },
+            _ => char::from_u32(code),
+        };
+        self.high_surrogate = None;
+        match ch {
+            Some(ch) => {
+                self.text.push(ch);
+                true
+            },
+            None => false,
+        }
+    }
 }
 
 struct PendingKeyDown {
+    window_id: WindowId,
+    device_id: Option<DeviceId>,
+    timestamp: Option<Duration>,
     scancode: u16,
     event: winit_core::event::KeyEvent,
 }
 
-#[derive(Clone, Copy)]
-struct AppPtr(*mut (dyn ApplicationHandler + 'static));
-
-unsafe impl Send for AppPtr {}
-unsafe impl Sync for AppPtr {}
+/// State tracked per view, since views may live on monitors with different scale factors.
+struct ViewState {
+    display_info: Option<AgileReference<DisplayInformation>>,
+    surface_size: PhysicalSize<u32>,
+    scale_factor: f64,
+    /// Theme set with `Window::set_theme`, overriding the system theme.
+    theme: Option<Theme>,
+    /// Size set with `Window::set_max_surface_size`, enforced by resizing the view.
+    max_surface_size: Option<Size>,
+    /// Size the view is kept at while it isn't resizable, see `Window::set_resizable`.
+    pinned_surface_size: Option<LogicalSize<f64>>,
+    /// Position of the `CoreWindow` on the desktop, last reported as `WindowEvent::Moved`.
+    position: PhysicalPosition<i32>,
+}
 
 pub(crate) struct Runner {
-    app: Mutex<Option<AppPtr>>,
+    /// The payload of a panic caught on the view thread, resumed by the caller of
+    /// `CoreApplication::Run`.
+    panic: Mutex<Option<Box<dyn Any + Send>>>,
+    /// The function called on the view thread, see `EventLoop::run_view_never_return`.
+    view_main: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+    /// Whether `pump_events` already delivered `StartCause::Init`.
+    pump_started: AtomicBool,
     pub(crate) control_flow: Mutex<ControlFlow>,
     pub(crate) exit: AtomicBool,
-    events: Mutex<VecDeque<Event>>,
+    /// The status passed to `std::process::exit` once the event loop is done.
+    exit_code: AtomicI32,
+    /// Events queued by the WinRT callbacks. The channel is lock-free on the sending side, the
+    /// receiver is only locked by the thread that dispatches the events.
+    event_sender: mpsc::Sender<Event>,
+    event_receiver: Mutex<mpsc::Receiver<Event>>,
+    /// The number of events sent but not dispatched yet.
+    queued_events: AtomicUsize,
+    /// Windows that requested a redraw, coalesced into a single `RedrawRequested` per iteration.
+    redraw_requests: Mutex<HashSet<WindowId>>,
+    /// Whether redraws are always delivered at the vertical blank.
+    vsync_redraws: bool,
+    /// Whether the `CoreWindow` is activated by the application instead of in `SetWindow`.
+    deferred_activation: bool,
+    /// Whether the next redraws are held back until the vertical blank, see `pre_present_notify`.
+    frame_pending: AtomicBool,
+    /// The thread waiting for vertical blanks, cleared to stop it.
+    vblank_thread: Mutex<Option<std::thread::Thread>>,
+    /// Whether the vblank thread waits for vertical blanks, cleared if it can't wait for them.
+    vblank_available: AtomicBool,
+    /// Whether a vertical blank occurred since the last redraws were delivered.
+    vblank: AtomicBool,
     window: Mutex<Option<AgileReference<WinRtCoreWindow>>>,
     dispatcher: Mutex<Option<AgileReference<CoreDispatcher>>>,
-    display_info: Mutex<Option<AgileReference<DisplayInformation>>>,
-    surface_size: Mutex<PhysicalSize<u32>>,
-    scale_factor_bits: AtomicU64,
+    gesture_recognizer: Mutex<Option<AgileReference<GestureRecognizer>>>,
+    views: Mutex<HashMap<WindowId, ViewState>>,
     has_focus: AtomicBool,
+    /// When device events are delivered, see `ActiveEventLoop::listen_device_events`.
+    device_events: Mutex<DeviceEvents>,
+    /// The `MouseDevice::MouseMoved` registration, removed while device events are disabled.
+    mouse_moved_token: Mutex<Option<EventRegistrationToken>>,
+    layout_rtl: AtomicBool,
+    /// Kept alive so that `ColorValuesChanged` keeps firing.
+    ui_settings: Mutex<Option<UISettings>>,
+    system_theme: Mutex<Option<Theme>>,
+    animations_enabled: AtomicBool,
     pub(crate) window_created: AtomicBool,
+    /// Whether `WindowEvent::Destroyed` was already reported by `Runner::close_view`.
+    window_closed: AtomicBool,
     wakeup_pending: AtomicBool,
+    /// Whether `process_os_events` is blocked in `CoreProcessEventsOption::ProcessUntilQuit`.
+    waiting: AtomicBool,
+    /// Whether the event loop runs without a view, see `EventLoopBuilderExtWinRt::with_headless`.
+    pub(crate) headless: bool,
+    /// The thread waiting in headless mode, unparked by `wake_up`.
+    headless_thread: Mutex<Option<std::thread::Thread>>,
+    /// Whether the event loop runs in a view owned by the host, see
+    /// `EventLoopBuilderExtWinRt::with_attach_to_current_view`.
+    pub(crate) attached: bool,
+    /// Whether the event loop runs on a `DispatcherQueue` instead of a view, see
+    /// `EventLoopBuilderExtWinRt::with_dispatcher_queue`.
+    pub(crate) app_sdk: bool,
+    dispatcher_queue: Mutex<Option<DispatcherQueue>>,
+    /// The controller of `dispatcher_queue` if winit created it, which keeps the queue alive.
+    dispatcher_queue_controller: Mutex<Option<DispatcherQueueController>>,
+    /// The priority `stop_processing_events` dispatches with.
+    dispatcher_priority: CoreDispatcherPriority,
     pending_keydown: Mutex<Option<PendingKeyDown>>,
+    received_text: Mutex<ReceivedText>,
+    /// The vertical and horizontal wheel scroll settings, with the time of the last wheel event.
+    wheel_scroll_lines: Mutex<[Option<(Instant, f32)>; 2]>,
+    /// Buttons currently held per pointer ID, released when the pointer capture is lost.
+    pressed_buttons: Mutex<HashMap<u32, Vec<ButtonSource>>>,
+    event_timestamp: Mutex<Option<Duration>>,
+    cursor_resources: Mutex<HashMap<CustomCursorSource, u32>>,
+    launch_arguments: Mutex<Option<LaunchArguments>>,
+    /// Whether the `KeyboardDeliveryInterceptor` handlers were registered.
+    key_interception_registered: AtomicBool,
+    /// The target for composition visuals of the view, see `composition_target`.
+    composition_target: Mutex<Option<AgileReference<CompositionTarget>>>,
+    /// Whether the window is hosted in a `SwapChainPanel`, which then reports the surface size,
+    /// the scale factor and the pointer input instead of the `CoreWindow`.
+    panel_hosted: AtomicBool,
+    /// The pointer devices delivered on the independent input thread.
+    independent_input: InputDevices,
+    /// Whether a `HolographicSpace` is created for the window.
+    pub(crate) holographic: bool,
+    holographic_space: Mutex<Option<AgileReference<HolographicSpace>>>,
+    /// The cameras of the holographic space by ID.
+    holographic_cameras: Mutex<HashMap<u32, AgileReference<HolographicCamera>>>,
+    /// The ID of the window, derived from the `CoreWindow` once it is set.
+    window_id: AtomicUsize,
+    /// Whether the attention badge is shown, see `Window::request_user_attention`.
+    attention_badge: AtomicBool,
+    attention_toast: Mutex<Option<AgileReference<ToastNotification>>>,
+    coalesced_pointer_history: bool,
+    preferred_launch_mode: Option<LaunchWindowingMode>,
+    back_requested_as_close: bool,
+    close_confirmation: bool,
 }
 
 impl Runner {
-    fn new() -> Self {
+    fn new(attributes: &PlatformSpecificEventLoopAttributes) -> Self {
+        let (event_sender, event_receiver) = mpsc::channel();
+        let mut views = HashMap::new();
+        if let Some(surface) = attributes.headless {
+            views.insert(
+                HEADLESS_WINDOW_ID,
+                ViewState {
+                    display_info: None,
+                    surface_size: surface.size,
+                    scale_factor: surface.scale_factor,
+                    theme: None,
+                    max_surface_size: None,
+                    pinned_surface_size: None,
+                    position: PhysicalPosition::new(0, 0),
+                },
+            );
+        }
         Self {
-            app: Mutex::new(None),
+            panic: Mutex::new(None),
+            view_main: Mutex::new(None),
+            pump_started: AtomicBool::new(false),
             control_flow: Mutex::new(ControlFlow::default()),
             exit: AtomicBool::new(false),
-            events: Mutex::new(VecDeque::new()),
+            exit_code: AtomicI32::new(0),
+            event_sender,
+            event_receiver: Mutex::new(event_receiver),
+            queued_events: AtomicUsize::new(0),
+            redraw_requests: Mutex::new(HashSet::new()),
+            vsync_redraws: attributes.vsync_redraws,
+            deferred_activation: attributes.deferred_activation,
+            frame_pending: AtomicBool::new(false),
+            vblank_thread: Mutex::new(None),
+            vblank_available: AtomicBool::new(false),
+            vblank: AtomicBool::new(false),
             window: Mutex::new(None),
             dispatcher: Mutex::new(None),
-            display_info: Mutex::new(None),
-            surface_size: Mutex::new(PhysicalSize::new(0, 0)),
-            scale_factor_bits: AtomicU64::new(f64::to_bits(1.0)),
+            gesture_recognizer: Mutex::new(None),
+            views: Mutex::new(views),
             has_focus: AtomicBool::new(false),
+            device_events: Mutex::new(DeviceEvents::default()),
+            mouse_moved_token: Mutex::new(None),
+            layout_rtl: AtomicBool::new(layout_is_right_to_left()),
+            ui_settings: Mutex::new(None),
+            system_theme: Mutex::new(system_theme()),
+            animations_enabled: AtomicBool::new(
+                UISettings::new().and_then(|settings| settings.AnimationsEnabled()).unwrap_or(true),
+            ),
             window_created: AtomicBool::new(false),
+            window_closed: AtomicBool::new(false),
             wakeup_pending: AtomicBool::new(false),
+            waiting: AtomicBool::new(false),
+            headless: attributes.headless.is_some(),
+            headless_thread: Mutex::new(None),
+            attached: attributes.attach_to_current_view,
+            app_sdk: attributes.dispatcher_queue,
+            dispatcher_queue: Mutex::new(None),
+            dispatcher_queue_controller: Mutex::new(None),
+            dispatcher_priority: match attributes.dispatcher_priority {
+                DispatcherPriority::Idle => CoreDispatcherPriority::Idle,
+                DispatcherPriority::Low => CoreDispatcherPriority::Low,
+                DispatcherPriority::Normal => CoreDispatcherPriority::Normal,
+                DispatcherPriority::High => CoreDispatcherPriority::High,
+            },
             pending_keydown: Mutex::new(None),
+            received_text: Mutex::new(ReceivedText::default()),
+            wheel_scroll_lines: Mutex::new([None; 2]),
+            pressed_buttons: Mutex::new(HashMap::new()),
+            event_timestamp: Mutex::new(None),
+            cursor_resources: Mutex::new(HashMap::new()),
+            launch_arguments: Mutex::new(None),
+            key_interception_registered: AtomicBool::new(false),
+            composition_target: Mutex::new(None),
+            panel_hosted: AtomicBool::new(false),
+            independent_input: attributes.independent_input,
+            holographic: attributes.holographic,
+            holographic_space: Mutex::new(None),
+            holographic_cameras: Mutex::new(HashMap::new()),
+            window_id: AtomicUsize::new(HEADLESS_WINDOW_ID.into_raw()),
+            attention_badge: AtomicBool::new(false),
+            attention_toast: Mutex::new(None),
+            coalesced_pointer_history: attributes.coalesced_pointer_history,
+            preferred_launch_mode: attributes.preferred_launch_mode,
+            back_requested_as_close: attributes.back_requested_as_close,
+            close_confirmation: attributes.close_confirmation,
         }
     }
 
@@ -241,36 +683,369 @@ impl Runner {
         self.dispatcher.lock().unwrap().as_ref().and_then(|agile| agile.resolve().ok())
     }
 
-    pub(crate) fn surface_size(&self) -> PhysicalSize<u32> {
-        *self.surface_size.lock().unwrap()
+    /// Runs `f` on the view thread the next time its dispatcher processes events.
+    pub(crate) fn run_on_dispatcher(
+        self: &Arc<Self>,
+        f: Box<dyn FnOnce() + Send>,
+    ) -> Result<(), RequestError> {
+        let Some(dispatcher) = self.dispatcher() else {
+            return self.run_on_dispatcher_queue(f);
+        };
+        let runner = Arc::clone(self);
+        let f = Mutex::new(Some(f));
+        let handler = DispatchedHandler::new(move || {
+            let Some(f) = f.lock().unwrap().take() else {
+                return Ok(());
+            };
+            runner.catch_panic(|| {
+                f();
+                Ok(())
+            })
+        });
+        dispatcher.RunAsync(self.dispatcher_priority, &handler).map_err(|err| os_error!(err))?;
+        Ok(())
+    }
+
+    /// Runs `f` on the thread of the `DispatcherQueue`, see
+    /// `EventLoopBuilderExtWinRt::with_dispatcher_queue`.
+    fn run_on_dispatcher_queue(
+        self: &Arc<Self>,
+        f: Box<dyn FnOnce() + Send>,
+    ) -> Result<(), RequestError> {
+        let Some(queue) = self.dispatcher_queue.lock().unwrap().clone() else {
+            return Err(NotSupportedError::new("CoreDispatcher is not available").into());
+        };
+        let runner = Arc::clone(self);
+        let f = Mutex::new(Some(f));
+        let handler = DispatcherQueueHandler::new(move || {
+            let Some(f) = f.lock().unwrap().take() else {
+                return Ok(());
+            };
+            runner.catch_panic(|| {
+                f();
+                Ok(())
+            })
+        });
+        match queue.TryEnqueue(&handler) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(NotSupportedError::new("the DispatcherQueue is shut down").into()),
+            Err(err) => Err(os_error!(err).into()),
+        }
+    }
+
+    /// Runs `f` on the thread of the `DispatcherQueue`, directly if that is the calling thread,
+    /// and otherwise without waiting for it.
+    pub(crate) fn on_dispatcher_queue_thread(self: &Arc<Self>, f: impl FnOnce() + Send + 'static) {
+        let queue = self.dispatcher_queue.lock().unwrap().clone();
+        if queue.is_none_or(|queue| queue.HasThreadAccess().unwrap_or(true)) {
+            f();
+        } else if let Err(err) = self.run_on_dispatcher_queue(Box::new(f)) {
+            warn!("failed to run on the DispatcherQueue thread: {err}");
+        }
+    }
+
+    /// Runs `f` on the view thread and waits for its result.
+    ///
+    /// Most `CoreWindow` and `ApplicationView` methods fail on other threads. `f` runs directly
+    /// when already on the view thread or without a view. Returns `None` if the dispatcher
+    /// dropped `f` without running it, e.g. because the view was closed.
+    pub(crate) fn on_view_thread<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> Option<R> {
+        let dispatcher = match self.dispatcher() {
+            Some(dispatcher) if !dispatcher.HasThreadAccess().unwrap_or(true) => dispatcher,
+            _ => return Some(f()),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let job = Mutex::new(Some(move || {
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        }));
+        let handler = DispatchedHandler::new(move || {
+            if let Some(job) = job.lock().unwrap().take() {
+                job();
+            }
+            Ok(())
+        });
+        let _ = dispatcher.RunAsync(self.dispatcher_priority, &handler);
+        drop(handler);
+
+        // Fails once the dispatcher dropped the job without running it.
+        match receiver.recv().ok()? {
+            Ok(result) => Some(result),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    pub(crate) fn exit_with_code(&self, code: i32) {
+        self.exit_code.store(code, Ordering::SeqCst);
+        self.exit.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn system_theme(&self) -> Option<Theme> {
+        *self.system_theme.lock().unwrap()
+    }
+
+    fn gesture_recognizer(&self) -> Option<GestureRecognizer> {
+        self.gesture_recognizer.lock().unwrap().as_ref().and_then(|agile| agile.resolve().ok())
+    }
+
+    pub(crate) fn surface_size(&self, window_id: WindowId) -> PhysicalSize<u32> {
+        self.views
+            .lock()
+            .unwrap()
+            .get(&window_id)
+            .map(|view| view.surface_size)
+            .unwrap_or(PhysicalSize::new(0, 0))
+    }
+
+    pub(crate) fn scale_factor(&self, window_id: WindowId) -> f64 {
+        self.views.lock().unwrap().get(&window_id).map(|view| view.scale_factor).unwrap_or(1.0)
+    }
+
+    pub(crate) fn window_id(&self) -> WindowId {
+        WindowId::from_raw(self.window_id.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn monitor_handle(&self, window_id: WindowId) -> MonitorHandle {
+        let views = self.views.lock().unwrap();
+        match views.get(&window_id) {
+            Some(view) => MonitorHandle::new(view.scale_factor, view.display_info.clone()),
+            None => MonitorHandle::new(1.0, None),
+        }
+    }
+
+    /// Clears the state of a dropped window, so that a new window can be created.
+    pub(crate) fn destroy_window(&self, window_id: WindowId) {
+        self.pressed_buttons.lock().unwrap().clear();
+        if let Some(recognizer) = self.gesture_recognizer() {
+            let _ = recognizer.CompleteGesture();
+        }
+        self.set_theme_override(window_id, None);
+        self.set_max_surface_size(window_id, None);
+        self.set_pinned_surface_size(window_id, None);
+        if !self.window_closed.swap(false, Ordering::SeqCst) {
+            self.queue_window_event(window_id, WindowEvent::Destroyed);
+        }
+        self.window_created.store(false, Ordering::SeqCst);
+    }
+
+    /// Closes the view, see `WindowExtWinRt::close`.
+    pub(crate) fn close_view(self: &Arc<Self>, window_id: WindowId) {
+        ensure_winrt_initialized();
+        let Ok(operation) =
+            ApplicationView::GetForCurrentView().and_then(|view| view.TryConsolidateAsync())
+        else {
+            self.close_core_window(window_id);
+            return;
+        };
+        let runner = Arc::clone(self);
+        let _ = operation.SetCompleted(&AsyncOperationCompletedHandler::<bool>::new(
+            move |operation, _| {
+                runner.catch_panic(|| {
+                    let consolidated =
+                        operation.as_ref().and_then(|operation| operation.GetResults().ok());
+                    if consolidated == Some(true) {
+                        runner.report_window_closed(window_id);
+                    } else {
+                        // The completion isn't necessarily raised on the view thread.
+                        let closing_runner = Arc::clone(&runner);
+                        let _ = runner.run_on_dispatcher(Box::new(move || {
+                            closing_runner.close_core_window(window_id)
+                        }));
+                    }
+                    Ok(())
+                })
+            },
+        ));
+    }
+
+    fn close_core_window(&self, window_id: WindowId) {
+        if let Some(window) = self.core_window() {
+            if window.Close().is_ok() {
+                self.report_window_closed(window_id);
+            }
+        }
+    }
+
+    pub(crate) fn report_window_closed(&self, window_id: WindowId) {
+        if !self.window_closed.swap(true, Ordering::SeqCst) {
+            self.queue_window_event(window_id, WindowEvent::Destroyed);
+        }
+    }
+
+    pub(crate) fn theme_override(&self, window_id: WindowId) -> Option<Theme> {
+        self.views.lock().unwrap().get(&window_id)?.theme
+    }
+
+    pub(crate) fn set_theme_override(&self, window_id: WindowId, theme: Option<Theme>) {
+        if let Some(view) = self.views.lock().unwrap().get_mut(&window_id) {
+            view.theme = theme;
+        }
+    }
+
+    pub(crate) fn set_max_surface_size(&self, window_id: WindowId, max_size: Option<Size>) {
+        if let Some(view) = self.views.lock().unwrap().get_mut(&window_id) {
+            view.max_surface_size = max_size;
+        }
+    }
+
+    pub(crate) fn pinned_surface_size(&self, window_id: WindowId) -> Option<LogicalSize<f64>> {
+        self.views.lock().unwrap().get(&window_id)?.pinned_surface_size
+    }
+
+    pub(crate) fn set_pinned_surface_size(
+        &self,
+        window_id: WindowId,
+        size: Option<LogicalSize<f64>>,
+    ) {
+        if let Some(view) = self.views.lock().unwrap().get_mut(&window_id) {
+            view.pinned_surface_size = size;
+        }
+    }
+
+    /// Returns the size the view has to be resized to, if `size` exceeds the maximum surface size
+    /// or the view isn't resizable.
+    pub(crate) fn clamped_surface_size(
+        &self,
+        window_id: WindowId,
+        size: LogicalSize<f64>,
+    ) -> Option<LogicalSize<f64>> {
+        let views = self.views.lock().unwrap();
+        let view = views.get(&window_id)?;
+        if let Some(pinned_size) = view.pinned_surface_size {
+            return (pinned_size != size).then_some(pinned_size);
+        }
+        let max_size = view.max_surface_size?.to_logical::<f64>(view.scale_factor);
+        let clamped =
+            LogicalSize::new(size.width.min(max_size.width), size.height.min(max_size.height));
+        (clamped != size).then_some(clamped)
+    }
+
+    pub(crate) fn display_info(&self, window_id: WindowId) -> Option<DisplayInformation> {
+        ensure_winrt_initialized();
+        let views = self.views.lock().unwrap();
+        views.get(&window_id)?.display_info.as_ref()?.resolve().ok()
     }
 
-    pub(crate) fn scale_factor(&self) -> f64 {
-        f64::from_bits(self.scale_factor_bits.load(Ordering::Relaxed))
+    pub(crate) fn listen_device_events(self: &Arc<Self>, allowed: DeviceEvents) {
+        *self.device_events.lock().unwrap() = allowed;
+        if self.core_window().is_some() {
+            self.update_mouse_device_handlers();
+        }
     }
 
-    pub(crate) fn monitor_handle(&self) -> MonitorHandle {
-        MonitorHandle::new(self.scale_factor(), self.display_info.lock().unwrap().clone())
+    pub(crate) fn handle_focus_changed(&self, window_id: WindowId, focused: bool) {
+        self.has_focus.store(focused, Ordering::Relaxed);
+        if focused {
+            self.clear_user_attention();
+        }
+        self.queue_window_event(window_id, WindowEvent::Focused(focused));
     }
 
     pub(crate) fn has_focus(&self) -> bool {
         self.has_focus.load(Ordering::Relaxed)
     }
 
+    pub(crate) fn is_layout_right_to_left(&self) -> bool {
+        self.layout_rtl.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn animations_enabled(&self) -> bool {
+        self.animations_enabled.load(Ordering::Relaxed)
+    }
+
     pub(crate) fn queue_event(&self, event: Event) {
-        self.events.lock().unwrap().push_back(event);
+        // Counted before sending, so that the dispatching thread never sees a sent event that
+        // isn't counted yet.
+        self.queued_events.fetch_add(1, Ordering::SeqCst);
+        let _ = self.event_sender.send(event);
+        self.wake_up();
+    }
+
+    pub(crate) fn request_redraw(&self, window_id: WindowId) {
+        if self.redraw_requests.lock().unwrap().insert(window_id) {
+            self.wake_up();
+            self.unpark_vblank_thread();
+        }
     }
 
-    pub(crate) fn queue_window_event(&self, event: WindowEvent) {
-        self.queue_event(Event::Window { window_id: GLOBAL_WINDOW_ID, event });
+    /// Holds back the next redraws until the vertical blank, after the application presented.
+    pub(crate) fn pre_present_notify(self: &Arc<Self>) {
+        self.vblank.store(false, Ordering::SeqCst);
+        self.frame_pending.store(true, Ordering::SeqCst);
+        self.start_vblank_thread();
     }
 
-    pub(crate) fn wake_up(&self) {
-        if let Some(dispatcher) = self.dispatcher() {
-            let _ = dispatcher.RunAsync(
-                CoreDispatcherPriority::Normal,
-                &windows::UI::Core::DispatchedHandler::new(|| Ok(())),
-            );
+    /// Whether redraws wait for the vertical blank.
+    fn redraws_throttled(&self) -> bool {
+        self.vblank_available.load(Ordering::SeqCst)
+            && (self.vsync_redraws || self.frame_pending.load(Ordering::SeqCst))
+    }
+
+    /// Whether redraws are requested but wait for the vertical blank.
+    fn redraws_waiting(&self) -> bool {
+        self.redraws_throttled() && !self.redraw_requests.lock().unwrap().is_empty()
+    }
+
+    /// Whether `dispatch_redraws` has redraws to deliver.
+    fn redraws_ready(&self) -> bool {
+        !self.redraw_requests.lock().unwrap().is_empty()
+            && (!self.redraws_throttled() || self.vblank.load(Ordering::SeqCst))
+    }
+
+    pub(crate) fn queue_window_event(&self, window_id: WindowId, event: WindowEvent) {
+        self.queue_event(Event::Window { window_id, event, timestamp: None });
+    }
+
+    /// Queue an input event along with the time at which the OS says it occurred.
+    pub(crate) fn queue_input_event(
+        &self,
+        window_id: WindowId,
+        event: WindowEvent,
+        timestamp: Option<Duration>,
+    ) {
+        self.queue_event(Event::Window { window_id, event, timestamp });
+    }
+
+    pub(crate) fn event_timestamp(&self) -> Option<Duration> {
+        *self.event_timestamp.lock().unwrap()
+    }
+
+    pub(crate) fn register_cursor_resource(&self, source: CustomCursorSource, resource_id: u32) {
+        self.cursor_resources.lock().unwrap().insert(source, resource_id);
+    }
+
+    fn cursor_resource(&self, source: &CustomCursorSource) -> Option<u32> {
+        self.cursor_resources.lock().unwrap().get(source).copied()
+    }
+
+    /// Stops `process_os_events` from waiting, so that the queued events are dispatched.
+    fn wake_up(&self) {
+        if !self.waiting.load(Ordering::SeqCst) {
+            return;
+        }
+        if self.headless {
+            if let Some(thread) = &*self.headless_thread.lock().unwrap() {
+                thread.unpark();
+            }
+            return;
+        }
+        if self.app_sdk {
+            // Any message ends the wait in `wait_messages`.
+            if let Some(queue) = &*self.dispatcher_queue.lock().unwrap() {
+                let _ = queue.TryEnqueue(&DispatcherQueueHandler::new(|| Ok(())));
+            }
+            return;
+        }
+        let Some(dispatcher) = self.dispatcher() else {
+            return;
+        };
+        if dispatcher.HasThreadAccess().unwrap_or(false) {
+            let _ = dispatcher.StopProcessEvents();
+        } else {
+            stop_processing_events(&dispatcher, self.dispatcher_priority);
         }
     }
 
@@ -279,117 +1054,540 @@ impl Runner {
             return;
         }
         self.queue_event(Event::WakeUp);
+    }
+
+    fn set_view_main(&self, main: Box<dyn FnOnce() + Send>) {
+        *self.view_main.lock().unwrap() = Some(main);
+    }
+
+    fn take_view_main(&self) -> Option<Box<dyn FnOnce() + Send>> {
+        self.view_main.lock().unwrap().take()
+    }
+
+    /// Runs a WinRT callback.
+    ///
+    /// Unwinding into WinRT aborts the process, so a panic ends the event loop and is resumed by
+    /// its caller instead.
+    pub(crate) fn catch_panic<R>(&self, f: impl FnOnce() -> WinResult<R>) -> WinResult<R> {
+        panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+            self.store_panic(payload);
+            Err(E_UNEXPECTED.into())
+        })
+    }
+
+    /// Ends the event loop with a panic, keeping the first one if several callbacks panicked.
+    fn store_panic(&self, payload: Box<dyn Any + Send>) {
+        self.panic.lock().unwrap().get_or_insert(payload);
+        self.exit.store(true, Ordering::SeqCst);
         self.wake_up();
     }
 
-    fn set_app<A: ApplicationHandler + 'static>(&self, app: A) {
-        let mut slot = self.app.lock().unwrap();
-        if slot.is_some() {
-            return;
+    /// Resumes a panic caught in a WinRT callback.
+    fn resume_panic(&self) {
+        if let Some(payload) = self.panic.lock().unwrap().take() {
+            panic::resume_unwind(payload);
         }
-        let boxed: Box<dyn ApplicationHandler> = Box::new(app);
-        *slot = Some(AppPtr(Box::into_raw(boxed)));
     }
 
-    fn take_app(&self) -> Option<*mut (dyn ApplicationHandler + 'static)> {
-        self.app.lock().unwrap().take().map(|ptr| ptr.0)
+    pub(crate) fn request_user_attention(&self, request_type: UserAttentionType, title: &str) {
+        if self.has_focus.load(Ordering::Relaxed) {
+            return;
+        }
+        if attention::show_attention_badge().is_ok() {
+            self.attention_badge.store(true, Ordering::Relaxed);
+        }
+        if request_type == UserAttentionType::Critical {
+            let mut attention_toast = self.attention_toast.lock().unwrap();
+            if attention_toast.is_none() {
+                *attention_toast =
+                    attention::show_toast(title).and_then(|toast| AgileReference::new(&toast)).ok();
+            }
+        }
     }
 
-    fn app_ptr(&self) -> Option<*mut (dyn ApplicationHandler + 'static)> {
-        self.app.lock().unwrap().map(|ptr| ptr.0)
+    /// Removes the badge and toast shown by `request_user_attention`.
+    pub(crate) fn clear_user_attention(&self) {
+        if self.attention_badge.swap(false, Ordering::Relaxed) {
+            attention::clear_badge();
+        }
+        let toast = self.attention_toast.lock().unwrap().take();
+        if let Some(toast) = toast.and_then(|toast| toast.resolve().ok()) {
+            attention::hide_toast(&toast);
+        }
     }
 
-    fn set_window(self: &Arc<Self>, window: WinRtCoreWindow) {
+    pub(crate) fn set_key_interception(
+        self: &Arc<Self>,
+        window_id: WindowId,
+        intercept: bool,
+    ) -> bool {
         ensure_winrt_initialized();
-        if let Ok(agile) = AgileReference::new(&window) {
-            *self.window.lock().unwrap() = Some(agile);
+        // Requires the restricted `inputForegroundObservation` capability.
+        let Ok(interceptor) = KeyboardDeliveryInterceptor::GetForCurrentView() else {
+            return false;
+        };
+        if intercept && !self.key_interception_registered.swap(true, Ordering::SeqCst) {
+            for state in [ElementState::Pressed, ElementState::Released] {
+                let handler =
+                    TypedEventHandler::<KeyboardDeliveryInterceptor, KeyEventArgs>::new({
+                        let runner = Arc::clone(self);
+                        move |_, args| {
+                            runner.catch_panic(|| {
+                                if let Some(args) = args {
+                                    // Keep the key from being delivered to the `CoreWindow` as well.
+                                    args.SetHandled(true)?;
+                                    runner.handle_key(
+                                        window_id,
+                                        key_device_id(&args.DeviceId().unwrap_or_default()),
+                                        args.VirtualKey()?,
+                                        args.KeyStatus()?,
+                                        state,
+                                    );
+                                }
+                                Ok(())
+                            })
+                        }
+                    });
+                let _ = match state {
+                    ElementState::Pressed => interceptor.KeyDown(&handler),
+                    ElementState::Released => interceptor.KeyUp(&handler),
+                };
+            }
         }
-        if let Ok(dispatcher) = window.Dispatcher() {
-            *self.dispatcher.lock().unwrap() = AgileReference::new(&dispatcher).ok();
+        interceptor.SetIsInterceptionEnabledWhenInForeground(intercept).is_ok()
+    }
+
+    /// Returns the composition target of the view, created on the first call.
+    pub(crate) fn composition_target(&self) -> WinResult<CompositionTarget> {
+        let mut composition_target = self.composition_target.lock().unwrap();
+        if let Some(target) = &*composition_target {
+            return target.resolve();
         }
+        // A view only accepts a single target, so it's created once and shared.
+        ensure_winrt_initialized();
+        let target = Compositor::new()?.CreateTargetForCurrentView()?;
+        *composition_target = Some(AgileReference::new(&target)?);
+        Ok(target)
+    }
+
+    pub(crate) fn holographic_space(&self) -> Option<HolographicSpace> {
+        self.holographic_space.lock().unwrap().as_ref()?.resolve().ok()
+    }
 
-        if let Ok(info) = DisplayInformation::GetForCurrentView() {
-            let dpi = info.LogicalDpi().unwrap_or(96.0);
-            let scale = dpi_to_scale_factor(dpi as f64);
-            self.scale_factor_bits.store(f64::to_bits(scale), Ordering::Relaxed);
-            *self.display_info.lock().unwrap() = AgileReference::new(&info).ok();
+    pub(crate) fn holographic_camera(&self, camera_id: u32) -> Option<HolographicCamera> {
+        self.holographic_cameras.lock().unwrap().get(&camera_id)?.resolve().ok()
+    }
+
+    pub(crate) fn insert_holographic_camera(&self, camera_id: u32, camera: &HolographicCamera) {
+        if let Ok(camera) = AgileReference::new(camera) {
+            self.holographic_cameras.lock().unwrap().insert(camera_id, camera);
         }
+    }
 
-        let bounds = window.Bounds().unwrap_or_default();
-        let size = LogicalSize::new(bounds.Width as f64, bounds.Height as f64)
-            .to_physical::<u32>(self.scale_factor());
-        *self.surface_size.lock().unwrap() = size;
+    pub(crate) fn remove_holographic_camera(&self, camera_id: u32) {
+        self.holographic_cameras.lock().unwrap().remove(&camera_id);
+    }
 
-        self.register_window_handlers(&window);
-        self.register_display_handlers();
+    /// Whether the window is hosted in a `SwapChainPanel`, whose input source then delivers the
+    /// pointer input instead of the `CoreWindow`.
+    pub(crate) fn panel_hosted(&self) -> bool {
+        self.panel_hosted.load(Ordering::Relaxed)
     }
 
-    fn register_window_handlers(self: &Arc<Self>, window: &WinRtCoreWindow) {
-        let _ = window.Activated(
-            &TypedEventHandler::<WinRtCoreWindow, WindowActivatedEventArgs>::new({
-                let runner = Arc::clone(self);
-                move |_, args| {
-                    if let Some(args) = args {
-                        let active =
-                            args.WindowActivationState()? != CoreWindowActivationState::Deactivated;
-                        runner.has_focus.store(active, Ordering::Relaxed);
-                        runner.queue_window_event(WindowEvent::Focused(active));
-                    }
-                    Ok(())
-                }
-            }),
-        );
+    pub(crate) fn set_panel_hosted(&self, hosted: bool) {
+        self.panel_hosted.store(hosted, Ordering::Relaxed);
+    }
 
-        let _ = window.SizeChanged(
-            &TypedEventHandler::<WinRtCoreWindow, WindowSizeChangedEventArgs>::new({
-                let runner = Arc::clone(self);
-                move |_, args| {
-                    if let Some(args) = args {
-                        runner.handle_size_changed(args);
+    /// Returns a pointer which currently has a button pressed.
+    pub(crate) fn pressed_pointer(&self) -> Option<u32> {
+        self.pressed_buttons.lock().unwrap().keys().next().copied()
+    }
+
+    pub(crate) fn launch_arguments(&self) -> Option<LaunchArguments> {
+        self.launch_arguments.lock().unwrap().clone()
+    }
+
+    fn register_activation_handler(self: &Arc<Self>, view: &CoreApplicationView) {
+        let runner = Arc::clone(self);
+        let _ = view.Activated(
+            &TypedEventHandler::<CoreApplicationView, IActivatedEventArgs>::new(move |_, args| {
+                runner.catch_panic(|| {
+                    // Activated is raised again when the application is launched while running.
+                    let launch = args
+                        .as_ref()
+                        .and_then(|args| args.cast::<LaunchActivatedEventArgs>().ok())
+                        .map(|args| LaunchArguments {
+                            arguments: args.Arguments().unwrap_or_default().to_string_lossy(),
+                            tile_id: args.TileId().unwrap_or_default().to_string_lossy(),
+                        });
+                    if let Some(launch) = launch {
+                        *runner.launch_arguments.lock().unwrap() = Some(launch.clone());
+                        runner.queue_event(Event::WinRt(WinRtEvent::LaunchActivated(launch)));
                     }
                     Ok(())
-                }
+                })
             }),
         );
+    }
 
-        let _ = window.Closed(&TypedEventHandler::<WinRtCoreWindow, CoreWindowEventArgs>::new({
-            let runner = Arc::clone(self);
-            move |_, _| {
-                runner.queue_window_event(WindowEvent::CloseRequested);
-                Ok(())
-            }
-        }));
-
+    fn register_lifecycle_handlers(self: &Arc<Self>) {
+        // Deferrals are held until the application has handled the corresponding event, so that
+        // it gets a chance to save state and release resources before the OS moves on.
         let _ =
-            window.PointerMoved(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
+            CoreApplication::EnteredBackground(&EventHandler::<EnteredBackgroundEventArgs>::new({
                 let runner = Arc::clone(self);
                 move |_, args| {
-                    if let Some(args) = args {
-                        runner.handle_pointer_moved(args);
-                    }
-                    Ok(())
+                    runner.catch_panic(|| {
+                        let deferral = args.as_ref().and_then(|args| args.GetDeferral().ok());
+                        runner.queue_event(Event::Suspended(deferral));
+                        Ok(())
+                    })
                 }
             }));
 
         let _ =
-            window.PointerPressed(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
+            CoreApplication::LeavingBackground(&EventHandler::<LeavingBackgroundEventArgs>::new({
                 let runner = Arc::clone(self);
-                move |_, args| {
-                    if let Some(args) = args {
-                        runner.handle_pointer_button(args, ElementState::Pressed);
-                    }
-                    Ok(())
+                move |_, _| {
+                    runner.catch_panic(|| {
+                        runner.queue_event(Event::Resumed);
+                        Ok(())
+                    })
                 }
             }));
 
-        let _ =
-            window.PointerReleased(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
-                let runner = Arc::clone(self);
-                move |_, args| {
+        let _ = CoreApplication::Suspending(&EventHandler::<SuspendingEventArgs>::new({
+            let runner = Arc::clone(self);
+            move |_, args| {
+                runner.catch_panic(|| {
+                    let deferral = args
+                        .as_ref()
+                        .and_then(|args| args.SuspendingOperation().ok())
+                        .and_then(|operation| operation.GetDeferral().ok());
+                    runner.queue_event(Event::DestroySurfaces(deferral));
+                    Ok(())
+                })
+            }
+        }));
+
+        let _ = CoreApplication::Resuming(&EventHandler::<IInspectable>::new({
+            let runner = Arc::clone(self);
+            move |_, _| {
+                runner.catch_panic(|| {
+                    runner.queue_event(Event::CanCreateSurfaces);
+                    Ok(())
+                })
+            }
+        }));
+    }
+
+    /// Registers the handlers the `IFrameworkView` would, on the view of the calling thread.
+    fn attach_to_current_view(self: &Arc<Self>) -> WinResult<()> {
+        let window = WinRtCoreWindow::GetForCurrentThread()?;
+        // The launch activation may already have happened, later ones are still reported.
+        if let Ok(view) = CoreApplication::GetCurrentView() {
+            self.register_activation_handler(&view);
+        }
+        self.register_lifecycle_handlers();
+        self.set_window(window);
+        Ok(())
+    }
+
+    /// Uses the `DispatcherQueue` of the calling thread, or creates one.
+    fn set_dispatcher_queue(&self) -> WinResult<()> {
+        let (queue, controller) = app_window::dispatcher_queue_for_current_thread()?;
+        *self.dispatcher_queue.lock().unwrap() = Some(queue);
+        *self.dispatcher_queue_controller.lock().unwrap() = controller;
+        Ok(())
+    }
+
+    /// Makes the `HWND` of an `AppWindow` the window of the event loop, see
+    /// `WindowAttributesWinRt::with_app_window`.
+    pub(crate) fn set_app_window(
+        &self,
+        window_id: WindowId,
+        surface_size: PhysicalSize<u32>,
+        scale_factor: f64,
+    ) {
+        self.window_id.store(window_id.into_raw(), Ordering::Relaxed);
+        self.views.lock().unwrap().insert(
+            window_id,
+            ViewState {
+                display_info: None,
+                surface_size,
+                scale_factor,
+                theme: None,
+                max_surface_size: None,
+                pinned_surface_size: None,
+                position: PhysicalPosition::new(0, 0),
+            },
+        );
+    }
+
+    fn set_window(self: &Arc<Self>, window: WinRtCoreWindow) {
+        ensure_winrt_initialized();
+        if let Ok(agile) = AgileReference::new(&window) {
+            *self.window.lock().unwrap() = Some(agile);
+        }
+        if let Ok(dispatcher) = window.Dispatcher() {
+            *self.dispatcher.lock().unwrap() = AgileReference::new(&dispatcher).ok();
+        }
+
+        // `DisplayInformation` is per view, so this must run on the view's thread.
+        let window_id = window_id_for(&window);
+        self.window_id.store(window_id.into_raw(), Ordering::Relaxed);
+        let info = DisplayInformation::GetForCurrentView().ok();
+        let scale_factor = info.as_ref().map(scale_factor_for).unwrap_or(1.0);
+        let bounds = window.Bounds().unwrap_or_default();
+        let surface_size = LogicalSize::new(bounds.Width as f64, bounds.Height as f64)
+            .to_physical::<u32>(scale_factor);
+        let position =
+            LogicalPosition::new(bounds.X as f64, bounds.Y as f64).to_physical::<i32>(scale_factor);
+        let display_info = info.as_ref().and_then(|info| AgileReference::new(info).ok());
+        self.views.lock().unwrap().insert(
+            window_id,
+            ViewState {
+                display_info,
+                surface_size,
+                scale_factor,
+                theme: None,
+                max_surface_size: None,
+                pinned_surface_size: None,
+                position,
+            },
+        );
+
+        // The holographic space must be created before the window is activated.
+        if self.holographic {
+            *self.holographic_space.lock().unwrap() =
+                holographic::create_holographic_space(self, &window, window_id);
+            holographic::register_spatial_input_handlers(self, window_id);
+        }
+
+        self.register_window_handlers(&window, window_id);
+        self.register_display_handlers(window_id);
+        self.register_language_handlers();
+        self.register_clipboard_handler();
+        self.register_ui_settings_handlers(window_id);
+        self.register_input_pane_handlers(window_id);
+        self.register_visible_bounds_handler(window_id);
+        self.update_mouse_device_handlers();
+        self.register_gesture_handlers(window_id);
+        self.register_title_bar_handlers(window_id);
+        drag_drop::register_drop_target(self, window_id);
+        if !self.independent_input.is_empty() {
+            independent_input::start_input_thread(self, window_id, self.independent_input);
+        }
+        if self.back_requested_as_close {
+            self.register_back_requested_handler(window_id);
+        }
+        if self.close_confirmation {
+            self.register_close_requested_handler(window_id);
+        }
+        if self.vsync_redraws {
+            self.start_vblank_thread();
+        }
+    }
+
+    fn start_vblank_thread(self: &Arc<Self>) {
+        let mut vblank_thread = self.vblank_thread.lock().unwrap();
+        if let Some(thread) = &*vblank_thread {
+            thread.unpark();
+            return;
+        }
+        // The thread doesn't keep the runner alive, and stops once the runner is dropped or
+        // `stop_vblank_thread` is called.
+        let runner = Arc::downgrade(self);
+        let spawned = std::thread::Builder::new().name("winit vblank".into()).spawn(move || {
+            // `IDXGIOutput::WaitForVBlank` blocks until the next vertical blank of the primary
+            // output.
+            let Ok(output) = primary_output() else {
+                return;
+            };
+            match runner.upgrade() {
+                Some(runner) => runner.vblank_available.store(true, Ordering::SeqCst),
+                None => return,
+            }
+            loop {
+                match runner.upgrade() {
+                    Some(runner) if runner.is_vblank_thread() => {
+                        if !runner.redraws_waiting() {
+                            // Unparked by `request_redraw` and `pre_present_notify`.
+                            drop(runner);
+                            std::thread::park();
+                            continue;
+                        }
+                    },
+                    _ => return,
+                }
+                if unsafe { output.WaitForVBlank() }.is_err() {
+                    break;
+                }
+                let Some(runner) = runner.upgrade() else {
+                    return;
+                };
+                runner.vblank.store(true, Ordering::SeqCst);
+                if runner.redraws_ready() {
+                    runner.wake_up();
+                }
+            }
+            // Fall back to delivering redraws right away.
+            if let Some(runner) = runner.upgrade() {
+                runner.vblank_available.store(false, Ordering::SeqCst);
+                runner.wake_up();
+            }
+        });
+        if let Ok(handle) = spawned {
+            *vblank_thread = Some(handle.thread().clone());
+        }
+    }
+
+    fn is_vblank_thread(&self) -> bool {
+        let vblank_thread = self.vblank_thread.lock().unwrap();
+        vblank_thread.as_ref().is_some_and(|thread| thread.id() == std::thread::current().id())
+    }
+
+    fn unpark_vblank_thread(&self) {
+        if let Some(thread) = &*self.vblank_thread.lock().unwrap() {
+            thread.unpark();
+        }
+    }
+
+    /// Stops the vblank thread after its current wait, once the event loop exited.
+    fn stop_vblank_thread(&self) {
+        if let Some(thread) = self.vblank_thread.lock().unwrap().take() {
+            thread.unpark();
+        }
+    }
+
+    fn register_window_handlers(self: &Arc<Self>, window: &WinRtCoreWindow, window_id: WindowId) {
+        let _ = window.Activated(
+            &TypedEventHandler::<WinRtCoreWindow, WindowActivatedEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args {
+                            let state = args.WindowActivationState()?;
+                            let active = state != CoreWindowActivationState::Deactivated;
+                            runner.has_focus.store(active, Ordering::Relaxed);
+                            if active {
+                                runner.clear_user_attention();
+                                let pointer_activated =
+                                    state == CoreWindowActivationState::PointerActivated;
+                                runner.queue_event(Event::WinRt(WinRtEvent::WindowActivated {
+                                    window_id,
+                                    pointer_activated,
+                                }));
+                            }
+                            runner.queue_window_event(window_id, WindowEvent::Focused(active));
+                        }
+                        Ok(())
+                    })
+                }
+            }),
+        );
+
+        let _ = window.SizeChanged(
+            &TypedEventHandler::<WinRtCoreWindow, WindowSizeChangedEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args {
+                            runner.handle_size_changed(window_id, args);
+                        }
+                        Ok(())
+                    })
+                }
+            }),
+        );
+
+        // An interactive resize raises `SizeChanged` repeatedly between these. They need Windows
+        // 10, version 1607.
+        let _ = window.ResizeStarted(&TypedEventHandler::<WinRtCoreWindow, IInspectable>::new({
+            let runner = Arc::clone(self);
+            move |_, _| {
+                runner.catch_panic(|| {
+                    runner.queue_event(Event::WinRt(WinRtEvent::ResizeStarted { window_id }));
+                    Ok(())
+                })
+            }
+        }));
+        let _ = window.ResizeCompleted(&TypedEventHandler::<WinRtCoreWindow, IInspectable>::new({
+            let runner = Arc::clone(self);
+            move |_, _| {
+                runner.catch_panic(|| {
+                    runner.queue_event(Event::WinRt(WinRtEvent::ResizeCompleted { window_id }));
+                    Ok(())
+                })
+            }
+        }));
+
+        let _ = window.Closed(&TypedEventHandler::<WinRtCoreWindow, CoreWindowEventArgs>::new({
+            let runner = Arc::clone(self);
+            move |_, _| {
+                runner.catch_panic(|| {
+                    runner.queue_window_event(window_id, WindowEvent::CloseRequested);
+                    Ok(())
+                })
+            }
+        }));
+
+        // The window becomes invisible when the application is minimized or hidden behind
+        // another full screen application.
+        let _ = window.VisibilityChanged(&TypedEventHandler::<
+            WinRtCoreWindow,
+            VisibilityChangedEventArgs,
+        >::new({
+            let runner = Arc::clone(self);
+            move |_, args| {
+                runner.catch_panic(|| {
                     if let Some(args) = args {
-                        runner.handle_pointer_button(args, ElementState::Released);
+                        let visible = args.Visible()?;
+                        runner.queue_window_event(window_id, WindowEvent::Occluded(!visible));
                     }
                     Ok(())
+                })
+            }
+        }));
+
+        // Over a hosting `SwapChainPanel`, the `CoreWindow` only sees the pointer input of the
+        // surrounding XAML content, in its own coordinates. The panel delivers its input itself.
+        let _ =
+            window.PointerMoved(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_moved(window_id, args);
+                        }
+                        Ok(())
+                    })
+                }
+            }));
+
+        let _ =
+            window.PointerPressed(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_button(window_id, args, ElementState::Pressed);
+                        }
+                        Ok(())
+                    })
+                }
+            }));
+
+        let _ =
+            window.PointerReleased(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_button(window_id, args, ElementState::Released);
+                        }
+                        Ok(())
+                    })
                 }
             }));
 
@@ -397,10 +1595,12 @@ impl Runner {
             window.PointerEntered(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
                 let runner = Arc::clone(self);
                 move |_, args| {
-                    if let Some(args) = args {
-                        runner.handle_pointer_entered(args);
-                    }
-                    Ok(())
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_entered(window_id, args);
+                        }
+                        Ok(())
+                    })
                 }
             }));
 
@@ -408,21 +1608,39 @@ impl Runner {
             window.PointerExited(&TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
                 let runner = Arc::clone(self);
                 move |_, args| {
-                    if let Some(args) = args {
-                        runner.handle_pointer_exited(args);
-                    }
-                    Ok(())
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_exited(window_id, args);
+                        }
+                        Ok(())
+                    })
                 }
             }));
 
+        let _ = window.PointerCaptureLost(
+            &TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_capture_lost(window_id, args);
+                        }
+                        Ok(())
+                    })
+                }
+            }),
+        );
+
         let _ = window.PointerWheelChanged(
             &TypedEventHandler::<WinRtCoreWindow, PointerEventArgs>::new({
                 let runner = Arc::clone(self);
                 move |_, args| {
-                    if let Some(args) = args {
-                        runner.handle_pointer_wheel(args);
-                    }
-                    Ok(())
+                    runner.catch_panic(|| {
+                        if let Some(args) = args.as_ref().filter(|_| !runner.panel_hosted()) {
+                            runner.handle_pointer_wheel(window_id, args);
+                        }
+                        Ok(())
+                    })
                 }
             }),
         );
@@ -430,20 +1648,36 @@ impl Runner {
         let _ = window.KeyDown(&TypedEventHandler::<WinRtCoreWindow, KeyEventArgs>::new({
             let runner = Arc::clone(self);
             move |_, args| {
-                if let Some(args) = args {
-                    runner.handle_key(args, ElementState::Pressed);
-                }
-                Ok(())
+                runner.catch_panic(|| {
+                    if let Some(args) = args {
+                        runner.handle_key(
+                            window_id,
+                            key_device_id(&args.DeviceId().unwrap_or_default()),
+                            args.VirtualKey()?,
+                            args.KeyStatus()?,
+                            ElementState::Pressed,
+                        );
+                    }
+                    Ok(())
+                })
             }
         }));
 
         let _ = window.KeyUp(&TypedEventHandler::<WinRtCoreWindow, KeyEventArgs>::new({
             let runner = Arc::clone(self);
             move |_, args| {
-                if let Some(args) = args {
-                    runner.handle_key(args, ElementState::Released);
-                }
-                Ok(())
+                runner.catch_panic(|| {
+                    if let Some(args) = args {
+                        runner.handle_key(
+                            window_id,
+                            key_device_id(&args.DeviceId().unwrap_or_default()),
+                            args.VirtualKey()?,
+                            args.KeyStatus()?,
+                            ElementState::Released,
+                        );
+                    }
+                    Ok(())
+                })
             }
         }));
 
@@ -453,126 +1687,799 @@ impl Runner {
         >::new({
             let runner = Arc::clone(self);
             move |_, args| {
-                if let Some(args) = args {
-                    runner.handle_character_received(args);
-                }
-                Ok(())
+                runner.catch_panic(|| {
+                    if let Some(args) = args {
+                        runner.handle_character_received(window_id, args);
+                    }
+                    Ok(())
+                })
             }
         }));
+
+        // Keys pressed together with Alt, and F10, are system keys that don't raise `KeyDown`.
+        if let Ok(dispatcher) = window.Dispatcher() {
+            let _ = dispatcher.AcceleratorKeyActivated(&TypedEventHandler::<
+                CoreDispatcher,
+                AcceleratorKeyEventArgs,
+            >::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        let Some(args) = args else {
+                            return Ok(());
+                        };
+                        let state = match args.EventType()? {
+                            CoreAcceleratorKeyEventType::SystemKeyDown => ElementState::Pressed,
+                            CoreAcceleratorKeyEventType::SystemKeyUp => ElementState::Released,
+                            _ => return Ok(()),
+                        };
+                        let device_id = key_device_id(&args.DeviceId().unwrap_or_default());
+                        runner.handle_key(
+                            window_id,
+                            device_id,
+                            args.VirtualKey()?,
+                            args.KeyStatus()?,
+                            state,
+                        );
+                        Ok(())
+                    })
+                }
+            }));
+        }
     }
 
-    fn register_display_handlers(self: &Arc<Self>) {
-        ensure_winrt_initialized();
-        let Some(info) =
-            self.display_info.lock().unwrap().as_ref().and_then(|agile| agile.resolve().ok())
-        else {
+    fn register_display_handlers(self: &Arc<Self>, window_id: WindowId) {
+        let Some(info) = self.display_info(window_id) else {
             return;
         };
         let runner = Arc::clone(self);
         let _ = info.DpiChanged(&TypedEventHandler::<DisplayInformation, IInspectable>::new(
             move |_, _| {
-                runner.handle_dpi_changed();
+                runner.catch_panic(|| {
+                    runner.handle_dpi_changed(window_id);
+                    Ok(())
+                })
+            },
+        ));
+        let runner = Arc::clone(self);
+        let _ = info.AdvancedColorInfoChanged(
+            &TypedEventHandler::<DisplayInformation, IInspectable>::new(move |info, _| {
+                runner.catch_panic(|| {
+                    let sdr_white_level = info
+                        .as_ref()
+                        .and_then(|info| info.GetAdvancedColorInfo().ok())
+                        .and_then(|info| info.SdrWhiteLevelInNits().ok())
+                        .unwrap_or(0.0);
+                    runner.queue_event(Event::WinRt(WinRtEvent::AdvancedColorInfoChanged {
+                        window_id,
+                        sdr_white_level,
+                    }));
+                    Ok(())
+                })
+            }),
+        );
+        let runner = Arc::clone(self);
+        let _ = info.ColorProfileChanged(
+            &TypedEventHandler::<DisplayInformation, IInspectable>::new(move |_, _| {
+                runner.catch_panic(|| {
+                    runner.queue_event(Event::WinRt(WinRtEvent::ColorProfileChanged { window_id }));
+                    Ok(())
+                })
+            }),
+        );
+        let runner = Arc::clone(self);
+        let _ = info.OrientationChanged(
+            &TypedEventHandler::<DisplayInformation, IInspectable>::new(move |_, _| {
+                runner.catch_panic(|| {
+                    runner.queue_event(Event::WinRt(WinRtEvent::SurfaceRotationChanged {
+                        window_id,
+                    }));
+                    Ok(())
+                })
+            }),
+        );
+    }
+
+    fn register_language_handlers(self: &Arc<Self>) {
+        // The `LayoutDirection` qualifier is updated when the application languages change.
+        let Ok(qualifiers) =
+            ResourceContext::GetForCurrentView().and_then(|context| context.QualifierValues())
+        else {
+            return;
+        };
+        let runner = Arc::clone(self);
+        let _ =
+            qualifiers.MapChanged(&MapChangedEventHandler::<HSTRING, HSTRING>::new(move |_, _| {
+                runner.catch_panic(|| {
+                    runner.handle_layout_direction_changed();
+                    Ok(())
+                })
+            }));
+
+        // The keyboard layout follows the input language, e.g. when switched with Win+Space.
+        if let Ok(manager) = CoreTextServicesManager::GetForCurrentView() {
+            let runner = Arc::clone(self);
+            let _ = manager.InputLanguageChanged(&TypedEventHandler::<
+                CoreTextServicesManager,
+                IInspectable,
+            >::new(move |manager, _| {
+                runner.catch_panic(|| {
+                    let language = manager
+                        .as_ref()
+                        .and_then(|manager| manager.InputLanguage().ok())
+                        .and_then(|language| language.LanguageTag().ok())
+                        .map(|tag| tag.to_string_lossy())
+                        .unwrap_or_default();
+                    runner.queue_event(Event::WinRt(WinRtEvent::InputLanguageChanged { language }));
+                    Ok(())
+                })
+            }));
+        }
+    }
+
+    fn register_clipboard_handler(self: &Arc<Self>) {
+        let runner = Arc::clone(self);
+        let _ = Clipboard::ContentChanged(&EventHandler::<IInspectable>::new(move |_, _| {
+            runner.catch_panic(|| {
+                runner.queue_event(Event::WinRt(WinRtEvent::ClipboardContentChanged));
                 Ok(())
+            })
+        }));
+    }
+
+    fn register_visible_bounds_handler(self: &Arc<Self>, window_id: WindowId) {
+        let Ok(view) = ApplicationView::GetForCurrentView() else {
+            return;
+        };
+        let runner = Arc::clone(self);
+        let _ = view.VisibleBoundsChanged(
+            &TypedEventHandler::<ApplicationView, IInspectable>::new(move |_, _| {
+                runner.catch_panic(|| {
+                    runner.refresh_surface_size(window_id);
+                    runner.refresh_position(window_id);
+                    Ok(())
+                })
+            }),
+        );
+    }
+
+    fn register_ui_settings_handlers(self: &Arc<Self>, window_id: WindowId) {
+        let Ok(settings) = UISettings::new() else {
+            return;
+        };
+        // The `UISettings` events are raised on a background thread.
+        let _ = settings.ColorValuesChanged(&TypedEventHandler::<UISettings, IInspectable>::new({
+            let runner = Arc::clone(self);
+            // This is raised for accent color changes too.
+            move |settings, _| {
+                runner.catch_panic(|| {
+                    if let Some(settings) = settings {
+                        runner.handle_theme_changed(window_id, theme_from_settings(settings));
+                    }
+                    Ok(())
+                })
+            }
+        }));
+        let _ = settings.AnimationsEnabledChanged(&TypedEventHandler::<
+            UISettings,
+            UISettingsAnimationsEnabledChangedEventArgs,
+        >::new({
+            let runner = Arc::clone(self);
+            move |settings, _| {
+                runner.catch_panic(|| {
+                    if let Some(settings) = settings {
+                        runner.handle_animations_enabled_changed(settings.AnimationsEnabled()?);
+                    }
+                    Ok(())
+                })
+            }
+        }));
+        *self.ui_settings.lock().unwrap() = Some(settings);
+    }
+
+    fn handle_animations_enabled_changed(&self, enabled: bool) {
+        if self.animations_enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            self.queue_event(Event::WinRt(WinRtEvent::AnimationsEnabledChanged { enabled }));
+        }
+    }
+
+    fn handle_theme_changed(&self, window_id: WindowId, theme: Option<Theme>) {
+        let Some(theme) = theme else {
+            return;
+        };
+        // Windows with a theme override keep their theme.
+        if self.system_theme.lock().unwrap().replace(theme) != Some(theme)
+            && self.theme_override(window_id).is_none()
+        {
+            self.queue_window_event(window_id, WindowEvent::ThemeChanged(theme));
+        }
+    }
+
+    fn handle_layout_direction_changed(&self) {
+        let right_to_left = layout_is_right_to_left();
+        if self.layout_rtl.swap(right_to_left, Ordering::Relaxed) != right_to_left {
+            self.queue_event(Event::WinRt(WinRtEvent::LayoutDirectionChanged { right_to_left }));
+        }
+    }
+
+    /// Must run before the window is activated to affect the current launch.
+    fn apply_preferred_launch_mode(&self) {
+        let Some(mode) = self.preferred_launch_mode else {
+            return;
+        };
+        let windowing_mode = match mode {
+            LaunchWindowingMode::Auto => ApplicationViewWindowingMode::Auto,
+            LaunchWindowingMode::Size(size) => {
+                let size = WinRtSize { Width: size.width as f32, Height: size.height as f32 };
+                let _ = ApplicationView::SetPreferredLaunchViewSize(size);
+                ApplicationViewWindowingMode::PreferredLaunchViewSize
+            },
+            LaunchWindowingMode::FullScreen => ApplicationViewWindowingMode::FullScreen,
+        };
+        let _ = ApplicationView::SetPreferredLaunchWindowingMode(windowing_mode);
+    }
+
+    fn register_back_requested_handler(self: &Arc<Self>, window_id: WindowId) {
+        let Ok(navigation) = SystemNavigationManager::GetForCurrentView() else {
+            return;
+        };
+        let runner = Arc::clone(self);
+        let _ = navigation.BackRequested(&EventHandler::<BackRequestedEventArgs>::new(
+            move |_, args| {
+                runner.catch_panic(|| {
+                    if let Some(args) = args {
+                        args.SetHandled(true)?;
+                    }
+                    runner.queue_window_event(window_id, WindowEvent::CloseRequested);
+                    Ok(())
+                })
             },
         ));
     }
 
-    fn handle_size_changed(&self, args: &WindowSizeChangedEventArgs) {
-        let size = args.Size().unwrap_or_default();
-        let physical = LogicalSize::new(size.Width as f64, size.Height as f64)
-            .to_physical::<u32>(self.scale_factor());
-        *self.surface_size.lock().unwrap() = physical;
-        self.queue_window_event(WindowEvent::SurfaceResized(physical));
+    fn register_close_requested_handler(self: &Arc<Self>, window_id: WindowId) {
+        let Ok(navigation) = SystemNavigationManagerPreview::GetForCurrentView() else {
+            return;
+        };
+        let runner = Arc::clone(self);
+        let _ = navigation.CloseRequested(&EventHandler::<
+            SystemNavigationCloseRequestedPreviewEventArgs,
+        >::new(move |_, args| {
+            runner.catch_panic(|| {
+                if let Some(args) = args {
+                    // The close is decided once the application handled the event.
+                    let deferral = args.GetDeferral().ok();
+                    runner.queue_event(Event::CloseRequested {
+                        window_id,
+                        args: args.clone(),
+                        deferral,
+                    });
+                }
+                Ok(())
+            })
+        }));
+    }
+
+    fn register_title_bar_handlers(self: &Arc<Self>, window_id: WindowId) {
+        let Some(title_bar) = core_title_bar() else {
+            return;
+        };
+        let runner = Arc::clone(self);
+        let _ = title_bar.LayoutMetricsChanged(&TypedEventHandler::<
+            CoreApplicationViewTitleBar,
+            IInspectable,
+        >::new(move |_, _| {
+            runner.catch_panic(|| {
+                runner.queue_event(Event::WinRt(WinRtEvent::TitleBarLayoutChanged { window_id }));
+                Ok(())
+            })
+        }));
+    }
+
+    /// Registers or removes the `MouseMoved` handler according to `device_events`.
+    fn update_mouse_device_handlers(self: &Arc<Self>) {
+        // `MouseMoved` reports raw relative motion, and keeps doing so while the cursor is locked.
+        let Ok(mouse) = MouseDevice::GetForCurrentView() else {
+            return;
+        };
+        let listen = *self.device_events.lock().unwrap() != DeviceEvents::Never;
+        let mut token = self.mouse_moved_token.lock().unwrap();
+        match (listen, token.take()) {
+            (true, None) => {
+                let runner = Arc::clone(self);
+                *token = mouse
+                    .MouseMoved(&TypedEventHandler::<MouseDevice, MouseEventArgs>::new(
+                        move |_, args| {
+                            runner.catch_panic(|| {
+                                let device_events = *runner.device_events.lock().unwrap();
+                                if device_events == DeviceEvents::WhenFocused && !runner.has_focus()
+                                {
+                                    return Ok(());
+                                }
+                                if let Some(args) = args {
+                                    let delta = args.MouseDelta()?;
+                                    runner.queue_event(Event::Device {
+                                        device_id: Some(mouse_device_id()),
+                                        event: DeviceEvent::PointerMotion {
+                                            delta: (delta.X as f64, delta.Y as f64),
+                                        },
+                                    });
+                                }
+                                Ok(())
+                            })
+                        },
+                    ))
+                    .ok();
+            },
+            (false, Some(registered)) => {
+                let _ = mouse.RemoveMouseMoved(registered);
+            },
+            (_, registered) => *token = registered,
+        }
+    }
+
+    fn register_gesture_handlers(self: &Arc<Self>, window_id: WindowId) {
+        let Ok(recognizer) = GestureRecognizer::new() else {
+            return;
+        };
+        let _ = recognizer.SetGestureSettings(
+            GestureSettings::DoubleTap
+                | GestureSettings::ManipulationTranslateX
+                | GestureSettings::ManipulationTranslateY
+                | GestureSettings::ManipulationRotate
+                | GestureSettings::ManipulationScale
+                | GestureSettings::ManipulationMultipleFingerPanning,
+        );
+
+        let _ = recognizer.Tapped(&TypedEventHandler::<GestureRecognizer, TappedEventArgs>::new({
+            let runner = Arc::clone(self);
+            move |_, args| {
+                runner.catch_panic(|| {
+                    if let Some(args) = args {
+                        if args.TapCount()? == 2 {
+                            runner.queue_window_event(
+                                window_id,
+                                WindowEvent::DoubleTapGesture { device_id: None },
+                            );
+                        }
+                    }
+                    Ok(())
+                })
+            }
+        }));
+
+        let _ = recognizer.ManipulationStarted(&TypedEventHandler::<
+            GestureRecognizer,
+            ManipulationStartedEventArgs,
+        >::new({
+            let runner = Arc::clone(self);
+            move |_, _| {
+                runner.catch_panic(|| {
+                    runner.handle_manipulation(window_id, TouchPhase::Started, Default::default());
+                    Ok(())
+                })
+            }
+        }));
+
+        let _ = recognizer.ManipulationUpdated(&TypedEventHandler::<
+            GestureRecognizer,
+            ManipulationUpdatedEventArgs,
+        >::new({
+            let runner = Arc::clone(self);
+            move |_, args| {
+                runner.catch_panic(|| {
+                    if let Some(args) = args {
+                        runner.handle_manipulation(window_id, TouchPhase::Moved, args.Delta()?);
+                    }
+                    Ok(())
+                })
+            }
+        }));
+
+        let _ = recognizer.ManipulationCompleted(&TypedEventHandler::<
+            GestureRecognizer,
+            ManipulationCompletedEventArgs,
+        >::new({
+            let runner = Arc::clone(self);
+            move |_, _| {
+                runner.catch_panic(|| {
+                    runner.handle_manipulation(window_id, TouchPhase::Ended, Default::default());
+                    Ok(())
+                })
+            }
+        }));
+
+        *self.gesture_recognizer.lock().unwrap() = AgileReference::new(&recognizer).ok();
+    }
+
+    fn handle_manipulation(
+        &self,
+        window_id: WindowId,
+        phase: TouchPhase,
+        delta: ManipulationDelta,
+    ) {
+        // `Scale` is a multiplicative factor and `Rotation` is clockwise, winit expects an
+        // additive magnification and a counterclockwise rotation. Updates only report the
+        // gestures that actually changed, while the start and end are reported for all of them.
+        let moved = phase == TouchPhase::Moved;
+        if !moved || (delta.Scale != 1.0 && delta.Scale != 0.0) {
+            let delta = if moved { delta.Scale as f64 - 1.0 } else { 0.0 };
+            self.queue_window_event(
+                window_id,
+                WindowEvent::PinchGesture { device_id: None, delta, phase },
+            );
+        }
+        if !moved || delta.Rotation != 0.0 {
+            self.queue_window_event(
+                window_id,
+                WindowEvent::RotationGesture { device_id: None, delta: -delta.Rotation, phase },
+            );
+        }
+        if !moved || delta.Translation.X != 0.0 || delta.Translation.Y != 0.0 {
+            let delta = LogicalPosition::new(delta.Translation.X, delta.Translation.Y)
+                .to_physical(self.scale_factor(window_id));
+            self.queue_window_event(
+                window_id,
+                WindowEvent::PanGesture { device_id: None, delta, phase },
+            );
+        }
+    }
+
+    /// Feed touch input to the gesture recognizer, which reports gestures through the handlers
+    /// registered in `register_gesture_handlers`.
+    fn process_gesture_input(
+        &self,
+        point: &PointerPoint,
+        args: &PointerEventArgs,
+        state: Option<ElementState>,
+    ) {
+        let is_touch = point.PointerDevice().and_then(|device| device.PointerDeviceType());
+        if !matches!(is_touch, Ok(PointerDeviceType::Touch)) {
+            return;
+        }
+        let Some(recognizer) = self.gesture_recognizer() else {
+            return;
+        };
+        let _ = match state {
+            Some(ElementState::Pressed) => recognizer.ProcessDownEvent(point),
+            Some(ElementState::Released) => recognizer.ProcessUpEvent(point),
+            None => args
+                .GetIntermediatePoints()
+                .and_then(|points| recognizer.ProcessMoveEvents(&points)),
+        };
+    }
+
+    fn register_input_pane_handlers(self: &Arc<Self>, window_id: WindowId) {
+        let Ok(input_pane) = InputPane::GetForCurrentView() else {
+            return;
+        };
+
+        let _ = input_pane.Showing(
+            &TypedEventHandler::<InputPane, InputPaneVisibilityEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args {
+                            // The application is told about the occluded area, so it is responsible for
+                            // keeping the focused element in view.
+                            let _ = args.SetEnsuredFocusedElementInView(true);
+                            let rect = args.OccludedRect()?;
+                            runner.handle_input_pane_occlusion(window_id, rect);
+                        }
+                        Ok(())
+                    })
+                }
+            }),
+        );
+
+        let _ = input_pane.Hiding(
+            &TypedEventHandler::<InputPane, InputPaneVisibilityEventArgs>::new({
+                let runner = Arc::clone(self);
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args {
+                            let _ = args.SetEnsuredFocusedElementInView(true);
+                        }
+                        runner.handle_input_pane_occlusion(window_id, Rect::default());
+                        Ok(())
+                    })
+                }
+            }),
+        );
+    }
+
+    fn handle_input_pane_occlusion(&self, window_id: WindowId, rect: Rect) {
+        let (position, size) = rect_to_physical(rect, self.scale_factor(window_id));
+        self.queue_event(Event::WinRt(WinRtEvent::InputPaneOcclusionChanged {
+            window_id,
+            position,
+            size,
+        }));
+    }
+
+    /// Re-reads the size of the `CoreWindow`, which follows the visible bounds unless the view
+    /// extends beyond them, see `WindowExtWinRt::set_extend_beyond_visible_bounds`.
+    pub(crate) fn refresh_surface_size(&self, window_id: WindowId) {
+        if self.panel_hosted() {
+            return;
+        }
+        let Some(Ok(bounds)) = self.core_window().map(|window| window.Bounds()) else {
+            return;
+        };
+        let physical = LogicalSize::new(bounds.Width as f64, bounds.Height as f64)
+            .to_physical::<u32>(self.scale_factor(window_id));
+        self.update_surface_size(window_id, physical);
+    }
+
+    /// Reports `WindowEvent::SurfaceResized` if the surface size changed.
+    pub(crate) fn update_surface_size(&self, window_id: WindowId, physical: PhysicalSize<u32>) {
+        let changed = match self.views.lock().unwrap().get_mut(&window_id) {
+            Some(view) if view.surface_size != physical => {
+                view.surface_size = physical;
+                true
+            },
+            _ => false,
+        };
+        if changed {
+            self.queue_window_event(window_id, WindowEvent::SurfaceResized(physical));
+        }
+    }
+
+    fn handle_size_changed(&self, window_id: WindowId, args: &WindowSizeChangedEventArgs) {
+        // The panel reports its own size, see `panel::PanelHost`.
+        if self.panel_hosted() {
+            return;
+        }
+        let size = args.Size().unwrap_or_default();
+        let physical = LogicalSize::new(size.Width as f64, size.Height as f64)
+            .to_physical::<u32>(self.scale_factor(window_id));
+        if let Some(view) = self.views.lock().unwrap().get_mut(&window_id) {
+            view.surface_size = physical;
+        }
+        self.queue_window_event(window_id, WindowEvent::SurfaceResized(physical));
+        self.refresh_position(window_id);
+
+        // WinRT has no maximum view size and no fixed size views, so the view is resized back
+        // instead. The system may refuse, e.g. in tablet mode.
+        let logical = LogicalSize::new(size.Width as f64, size.Height as f64);
+        if let Some(clamped) = self.clamped_surface_size(window_id, logical) {
+            if let Ok(view) = ApplicationView::GetForCurrentView() {
+                let _ = view.TryResizeView(WinRtSize {
+                    Width: clamped.width as f32,
+                    Height: clamped.height as f32,
+                });
+            }
+        }
+    }
+
+    /// Reports `WindowEvent::Moved` if the `CoreWindow` moved. WinRT has no move event, so this
+    /// is checked whenever the bounds of the view may have changed.
+    fn refresh_position(&self, window_id: WindowId) {
+        let Some(Ok(bounds)) = self.core_window().map(|window| window.Bounds()) else {
+            return;
+        };
+        let position = LogicalPosition::new(bounds.X as f64, bounds.Y as f64)
+            .to_physical::<i32>(self.scale_factor(window_id));
+        let moved = match self.views.lock().unwrap().get_mut(&window_id) {
+            Some(view) if view.position != position => {
+                view.position = position;
+                true
+            },
+            _ => false,
+        };
+        if moved {
+            self.queue_window_event(window_id, WindowEvent::Moved(position));
+        }
+    }
+
+    fn handle_dpi_changed(&self, window_id: WindowId) {
+        // The composition scale of the panel already includes the display scale.
+        if self.panel_hosted() {
+            return;
+        }
+        let Some(info) = self.display_info(window_id) else {
+            return;
+        };
+        self.update_scale_factor(window_id, scale_factor_for(&info));
+    }
+
+    /// Reports `WindowEvent::ScaleFactorChanged` if the scale factor changed. The surface size the
+    /// application writes back is applied once the event was dispatched.
+    pub(crate) fn update_scale_factor(&self, window_id: WindowId, new_scale: f64) {
+        let new_size_arc = {
+            let mut views = self.views.lock().unwrap();
+            let Some(view) = views.get_mut(&window_id) else {
+                return;
+            };
+            let old_scale = view.scale_factor;
+            if (new_scale - old_scale).abs() < f64::EPSILON {
+                return;
+            }
+            view.scale_factor = new_scale;
+            let new_size =
+                view.surface_size.to_logical::<f64>(old_scale).to_physical::<u32>(new_scale);
+            view.surface_size = new_size;
+            Arc::new(Mutex::new(new_size))
+        };
+
+        self.queue_event(Event::ScaleFactorChanged {
+            window_id,
+            scale_factor: new_scale,
+            surface_size: new_size_arc,
+        });
     }
 
-    fn handle_dpi_changed(&self) {
-        ensure_winrt_initialized();
-        let Some(info) =
-            self.display_info.lock().unwrap().as_ref().and_then(|agile| agile.resolve().ok())
-        else {
+    /// Resizes the window to the surface size the application wrote in `ScaleFactorChanged`.
+    fn apply_written_surface_size(&self, window_id: WindowId, size: PhysicalSize<u32>) {
+        if self.surface_size(window_id) == size {
             return;
-        };
-        let new_dpi = info.LogicalDpi().unwrap_or(96.0);
-        let new_scale = dpi_to_scale_factor(new_dpi as f64);
-        let old_scale = self.scale_factor();
-        if (new_scale - old_scale).abs() < f64::EPSILON {
+        }
+        // The XAML layout sizes the panel.
+        if self.panel_hosted() {
             return;
         }
-        self.scale_factor_bits.store(f64::to_bits(new_scale), Ordering::Relaxed);
-
-        let old_size = *self.surface_size.lock().unwrap();
-        let new_size = old_size.to_logical::<f64>(old_scale).to_physical::<u32>(new_scale);
-        let new_size_arc = Arc::new(Mutex::new(new_size));
-        self.queue_window_event(WindowEvent::ScaleFactorChanged {
-            scale_factor: new_scale,
-            surface_size_writer: winit_core::event::SurfaceSizeWriter::new(Arc::downgrade(
-                &new_size_arc,
-            )),
-        });
-        let updated = *new_size_arc.lock().unwrap();
-        *self.surface_size.lock().unwrap() = updated;
+        if self.app_sdk {
+            // The ID of an `AppWindow` is its `HWND`.
+            if let Some(hwnd) = NonZeroIsize::new(window_id.into_raw() as isize) {
+                app_window::resize_client_area(hwnd, size);
+            }
+            return;
+        }
+        let logical = size.to_logical::<f64>(self.scale_factor(window_id));
+        let size = WinRtSize { Width: logical.width as f32, Height: logical.height as f32 };
+        match ApplicationView::GetForCurrentView().and_then(|view| view.TryResizeView(size)) {
+            Ok(true) => {},
+            Ok(false) => warn!("The view can't be resized to the written surface size"),
+            Err(err) => warn!("Failed to resize the view: {err}"),
+        }
     }
 
-    fn handle_pointer_entered(&self, args: &PointerEventArgs) {
+    pub(crate) fn handle_pointer_entered(&self, window_id: WindowId, args: &PointerEventArgs) {
         let point = match args.CurrentPoint() {
             Ok(point) => point,
             Err(_) => return,
         };
-        let (position, primary, _source, kind) = self.pointer_details(&point);
-        self.queue_window_event(WindowEvent::PointerEntered {
-            device_id: None,
-            position,
-            primary,
-            kind,
-        });
+        let (position, primary, _source, kind) = self.pointer_details(window_id, &point);
+        let device_id = pointer_device_id(&point);
+        self.queue_input_event(
+            window_id,
+            WindowEvent::PointerEntered { device_id, position, primary, kind },
+            point_timestamp(&point),
+        );
     }
 
-    fn handle_pointer_exited(&self, args: &PointerEventArgs) {
+    pub(crate) fn handle_pointer_exited(&self, window_id: WindowId, args: &PointerEventArgs) {
         let point = match args.CurrentPoint() {
             Ok(point) => point,
             Err(_) => return,
         };
-        let (_, primary, _, kind) = self.pointer_details(&point);
-        self.queue_window_event(WindowEvent::PointerLeft {
-            device_id: None,
-            position: None,
-            primary,
-            kind,
-        });
+        let (_, primary, _, kind) = self.pointer_details(window_id, &point);
+        let device_id = pointer_device_id(&point);
+        self.queue_input_event(
+            window_id,
+            WindowEvent::PointerLeft { device_id, position: None, primary, kind },
+            point_timestamp(&point),
+        );
     }
 
-    fn handle_pointer_moved(&self, args: &PointerEventArgs) {
+    pub(crate) fn handle_pointer_moved(&self, window_id: WindowId, args: &PointerEventArgs) {
         let point = match args.CurrentPoint() {
             Ok(point) => point,
             Err(_) => return,
         };
-        let (position, primary, source, _) = self.pointer_details(&point);
-        self.queue_window_event(WindowEvent::PointerMoved {
-            device_id: None,
-            position,
-            primary,
-            source,
-        });
+        if self.coalesced_pointer_history {
+            // Intermediate points are ordered from newest to oldest, and include the current one.
+            if let Ok(points) = args.GetIntermediatePoints() {
+                for index in (0..points.Size().unwrap_or(0)).rev() {
+                    if let Ok(point) = points.GetAt(index) {
+                        self.queue_pointer_moved(window_id, &point);
+                    }
+                }
+            }
+        } else {
+            self.queue_pointer_moved(window_id, &point);
+        }
+        self.process_gesture_input(&point, args, None);
+    }
+
+    fn queue_pointer_moved(&self, window_id: WindowId, point: &PointerPoint) {
+        let (position, primary, source, _) = self.pointer_details(window_id, point);
+        let device_id = pointer_device_id(point);
+        self.queue_input_event(
+            window_id,
+            WindowEvent::PointerMoved { device_id, position, primary, source },
+            point_timestamp(point),
+        );
     }
 
-    fn handle_pointer_button(&self, args: &PointerEventArgs, state: ElementState) {
+    pub(crate) fn handle_pointer_button(
+        &self,
+        window_id: WindowId,
+        args: &PointerEventArgs,
+        state: ElementState,
+    ) {
         let point = match args.CurrentPoint() {
             Ok(point) => point,
             Err(_) => return,
         };
-        let (position, primary, source, _) = self.pointer_details(&point);
+        let (position, primary, source, _) = self.pointer_details(window_id, &point);
+        let device_id = pointer_device_id(&point);
         let props = point.Properties().ok();
         let button = button_source_from_point(props.as_ref(), &source);
-        self.queue_window_event(WindowEvent::PointerButton {
-            device_id: None,
-            state,
-            position,
-            primary,
-            button,
-        });
+        if let Ok(pointer_id) = point.PointerId() {
+            let mut pressed_buttons = self.pressed_buttons.lock().unwrap();
+            let buttons = pressed_buttons.entry(pointer_id).or_default();
+            buttons.retain(|pressed| !is_same_button(pressed, &button));
+            if state == ElementState::Pressed {
+                buttons.push(button.clone());
+            } else if buttons.is_empty() {
+                pressed_buttons.remove(&pointer_id);
+            }
+        }
+        self.queue_input_event(
+            window_id,
+            WindowEvent::PointerButton { device_id, state, position, primary, button },
+            point_timestamp(&point),
+        );
+        self.process_gesture_input(&point, args, Some(state));
+    }
+
+    pub(crate) fn handle_pointer_capture_lost(&self, window_id: WindowId, args: &PointerEventArgs) {
+        let point = match args.CurrentPoint() {
+            Ok(point) => point,
+            Err(_) => return,
+        };
+        let (position, primary, _, kind) = self.pointer_details(window_id, &point);
+        let device_id = pointer_device_id(&point);
+        let timestamp = point_timestamp(&point);
+
+        // The system took the pointer away, so the matching releases will never arrive.
+        let buttons = point
+            .PointerId()
+            .ok()
+            .and_then(|pointer_id| self.pressed_buttons.lock().unwrap().remove(&pointer_id))
+            .unwrap_or_default();
+        for button in buttons {
+            self.queue_input_event(
+                window_id,
+                WindowEvent::PointerButton {
+                    device_id,
+                    state: ElementState::Released,
+                    position,
+                    primary,
+                    button,
+                },
+                timestamp,
+            );
+        }
+        self.queue_input_event(
+            window_id,
+            WindowEvent::PointerLeft { device_id, position: Some(position), primary, kind },
+            timestamp,
+        );
+
+        if let Some(recognizer) = self.gesture_recognizer() {
+            let _ = recognizer.CompleteGesture();
+        }
     }
 
-    fn handle_pointer_wheel(&self, args: &PointerEventArgs) {
+    /// Returns the lines (or, for horizontal wheels, characters) to scroll per notch.
+    ///
+    /// The setting is read again at the start of each burst of wheel events, so that a change
+    /// applies to the next scroll without reading it for every event.
+    fn wheel_scroll_lines(&self, horizontal: bool) -> f32 {
+        let now = Instant::now();
+        let mut settings = self.wheel_scroll_lines.lock().unwrap();
+        let setting = &mut settings[usize::from(horizontal)];
+        let lines = match *setting {
+            Some((last_wheel, lines)) if now.duration_since(last_wheel) < WHEEL_BURST_GAP => lines,
+            _ => wheel_scroll_lines(horizontal),
+        };
+        *setting = Some((now, lines));
+        lines
+    }
+
+    pub(crate) fn handle_pointer_wheel(&self, window_id: WindowId, args: &PointerEventArgs) {
         let point = match args.CurrentPoint() {
             Ok(point) => point,
             Err(_) => return,
@@ -581,25 +2488,66 @@ impl Runner {
             Ok(props) => props,
             Err(_) => return,
         };
+        let device_id = pointer_device_id(&point);
         let delta = props.MouseWheelDelta().unwrap_or(0);
         let is_horizontal = props.IsHorizontalMouseWheel().unwrap_or(false);
-        let line = delta as f32 / 120.0;
-        let (x, y) = if is_horizontal { (line, 0.0) } else { (0.0, line) };
-        self.queue_window_event(WindowEvent::MouseWheel {
-            device_id: None,
-            delta: MouseScrollDelta::LineDelta(x, y),
-            phase: TouchPhase::Moved,
-        });
+        self.queue_wheel(window_id, device_id, delta, is_horizontal, point_timestamp(&point));
     }
 
-    fn handle_key(&self, args: &KeyEventArgs, state: ElementState) {
-        let virtual_key = args.VirtualKey().unwrap_or(VirtualKey::None);
-        let status = args.KeyStatus().unwrap_or_default();
-        let scancode = status.ScanCode as u16;
-        let repeat = status.RepeatCount > 1;
+    /// Reports a wheel delta in multiples or fractions of `WHEEL_DELTA`.
+    pub(crate) fn queue_wheel(
+        &self,
+        window_id: WindowId,
+        device_id: Option<DeviceId>,
+        delta: i32,
+        is_horizontal: bool,
+        timestamp: Option<Duration>,
+    ) {
+        // Precision touchpads report deltas smaller than a notch, while mouse wheels only report
+        // whole notches. Windows reports all mice and touchpads as the same pointer device, so
+        // this is decided for each event.
+        let precise = delta % WHEEL_DELTA != 0;
+        let notches = delta as f32 / WHEEL_DELTA as f32;
+        let delta = if precise {
+            let lines = notches * self.wheel_scroll_lines(is_horizontal);
+            let pixels = LogicalUnit::new(lines as f64 * WHEEL_LINE_PIXELS)
+                .to_physical::<f64>(self.scale_factor(window_id))
+                .0;
+            let (x, y) = if is_horizontal { (pixels, 0.0) } else { (0.0, pixels) };
+            MouseScrollDelta::PixelDelta(PhysicalPosition::new(x, y))
+        } else {
+            // Like on Win32, mouse wheels report notches, which applications scale themselves.
+            let (x, y) = if is_horizontal { (notches, 0.0) } else { (0.0, notches) };
+            MouseScrollDelta::LineDelta(x, y)
+        };
+        self.queue_input_event(
+            window_id,
+            WindowEvent::MouseWheel { device_id, delta, phase: TouchPhase::Moved },
+            timestamp,
+        );
+    }
+
+    pub(crate) fn handle_key(
+        &self,
+        window_id: WindowId,
+        device_id: Option<DeviceId>,
+        virtual_key: VirtualKey,
+        status: CorePhysicalKeyStatus,
+        state: ElementState,
+    ) {
+        // Deliver the text of the previous key first, so that the events stay in order.
+        self.flush_received_text();
+        // `KeyEventArgs` has no timestamp, the key is timestamped when it is received instead.
+        let timestamp = current_timestamp();
+
+        let scancode = key_scancode(&status);
+        // `RepeatCount` is usually 1 even for auto-repeated presses, `WasKeyDown` tells whether the
+        // key was already down before this event.
+        let repeat =
+            state == ElementState::Pressed && (status.WasKeyDown || status.RepeatCount > 1);
 
         let modifiers = self.current_modifiers();
-        self.queue_window_event(WindowEvent::ModifiersChanged(modifiers));
+        self.queue_window_event(window_id, WindowEvent::ModifiersChanged(modifiers));
 
         let (logical_key, text) = map_key(virtual_key, modifiers.state());
         let (key_without_modifiers, _) = map_key(virtual_key, ModifiersState::empty());
@@ -609,7 +2557,7 @@ impl Runner {
             physical_key: PhysicalKey::Unidentified(NativeKeyCode::Windows(scancode)),
             logical_key,
             text,
-            location: KeyLocation::Standard,
+            location: key_location(virtual_key, &status),
             state,
             repeat,
             text_with_all_modifiers,
@@ -631,15 +2579,12 @@ impl Runner {
 
             let pending_to_flush = self.pending_keydown.lock().unwrap().take();
             if let Some(pending) = pending_to_flush {
-                self.queue_window_event(WindowEvent::KeyboardInput {
-                    device_id: None,
-                    event: pending.event,
-                    is_synthetic: false,
-                });
+                self.flush_dead_key(pending);
             }
 
             if expect_text {
-                *self.pending_keydown.lock().unwrap() = Some(PendingKeyDown { scancode, event });
+                *self.pending_keydown.lock().unwrap() =
+                    Some(PendingKeyDown { window_id, device_id, timestamp, scancode, event });
                 return;
             }
         } else {
@@ -647,72 +2592,149 @@ impl Runner {
             if let Some(pending) = pending_lock.take() {
                 if pending.scancode == scancode {
                     drop(pending_lock);
-                    self.queue_window_event(WindowEvent::KeyboardInput {
-                        device_id: None,
-                        event: pending.event,
-                        is_synthetic: false,
-                    });
+                    self.flush_dead_key(pending);
+                    event.logical_key = Key::Dead(None);
                 } else {
                     *pending_lock = Some(pending);
                 }
             }
         }
 
-        self.queue_window_event(WindowEvent::KeyboardInput {
-            device_id: None,
-            event,
-            is_synthetic: false,
-        });
+        self.queue_input_event(
+            window_id,
+            WindowEvent::KeyboardInput { device_id, event, is_synthetic: false },
+            timestamp,
+        );
+    }
+
+    /// Emits a key press that never received its character as a dead key.
+    ///
+    /// Keys that produce text receive `CharacterReceived` before they are released, so a press
+    /// that is still pending when the key is released or another key is pressed started a dead-key
+    /// composition. The composed character arrives with the next key press.
+    fn flush_dead_key(&self, pending: PendingKeyDown) {
+        let mut event = pending.event;
+        event.logical_key = Key::Dead(None);
+        event.text = None;
+        event.text_with_all_modifiers = None;
+        self.queue_input_event(
+            pending.window_id,
+            WindowEvent::KeyboardInput { device_id: pending.device_id, event, is_synthetic: false },
+            pending.timestamp,
+        );
+    }
+
+    pub(crate) fn reset_dead_keys(&self) {
+        let pending = self.pending_keydown.lock().unwrap().take();
+        if let Some(pending) = pending {
+            self.flush_dead_key(pending);
+        }
     }
 
-    fn handle_character_received(&self, args: &CharacterReceivedEventArgs) {
+    fn handle_character_received(&self, window_id: WindowId, args: &CharacterReceivedEventArgs) {
         if let Ok(code) = args.KeyCode() {
-            if let Some(ch) = std::char::from_u32(code) {
-                let pending = self.pending_keydown.lock().unwrap().take();
-                if let Some(mut pending) = pending {
-                    let text = SmolStr::new(ch.to_string());
-                    pending.event.logical_key = Key::Character(text.clone());
-                    pending.event.text = Some(text.clone());
-                    pending.event.text_with_all_modifiers = Some(text);
-                    // pending.event.key_without_modifiers is kept from the original mapping.
-                    // It represents the key without modifiers.
-                    self.queue_window_event(WindowEvent::KeyboardInput {
-                        device_id: None,
-                        event: pending.event,
-                        is_synthetic: false,
-                    });
-                }
-            }
+            self.handle_character(window_id, code);
+        }
+    }
+
+    /// Receives a UTF-16 code unit of the text typed into the window.
+    pub(crate) fn handle_character(&self, window_id: WindowId, code: u32) {
+        let mut received = self.received_text.lock().unwrap();
+        received.window_id = Some(window_id);
+        if !received.push_code(code) {
+            return;
         }
+        drop(received);
+        // The text is delivered once the OS events were processed, a single key press or an
+        // emoji from the emoji panel can produce several characters.
+        self.wake_up();
+    }
+
+    /// Delivers the text received since the last key press.
+    ///
+    /// The text is attached to the pending key press. Text without a key press, e.g. from the
+    /// emoji panel or the handwriting panel, is delivered as a synthetic key press.
+    fn flush_received_text(&self) {
+        let (window_id, text) = {
+            let mut received = self.received_text.lock().unwrap();
+            if received.text.is_empty() {
+                return;
+            }
+            (received.window_id, std::mem::take(&mut received.text))
+        };
+        let text = SmolStr::new(text);
+        let pending = self.pending_keydown.lock().unwrap().take();
+        let (window_id, device_id, timestamp, mut event) = match pending {
+            Some(pending) => {
+                (pending.window_id, pending.device_id, pending.timestamp, pending.event)
+            },
+            None => {
+                let Some(window_id) = window_id else {
+                    return;
+                };
+                let event = winit_core::event::KeyEvent {
+                    physical_key: PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+                    logical_key: Key::Character(text.clone()),
+                    text: None,
+                    location: KeyLocation::Standard,
+                    state: ElementState::Pressed,
+                    repeat: false,
+                    text_with_all_modifiers: None,
+                    key_without_modifiers: Key::Character(text.clone()),
+                };
+                (window_id, None, current_timestamp(), event)
+            },
+        };
+        // `key_without_modifiers` is kept from the original mapping.
+        event.logical_key = Key::Character(text.clone());
+        event.text = Some(text.clone());
+        event.text_with_all_modifiers = Some(text);
+        self.queue_input_event(
+            window_id,
+            WindowEvent::KeyboardInput { device_id, event, is_synthetic: false },
+            timestamp,
+        );
     }
 
     fn current_modifiers(&self) -> Modifiers {
-        let Some(window) = self.core_window() else {
+        let window = self.core_window();
+        if window.is_none() && !self.app_sdk {
             return Modifiers::new(ModifiersState::empty(), ModifiersKeys::empty());
-        };
-        let shift = key_down(&window, VirtualKey::Shift);
-        let ctrl = key_down(&window, VirtualKey::Control);
-        let alt = key_down(&window, VirtualKey::Menu);
-        let meta = key_down(&window, VirtualKey::LeftWindows)
-            || key_down(&window, VirtualKey::RightWindows);
-        let mut state = ModifiersState::empty();
-        if shift {
-            state.insert(ModifiersState::SHIFT);
-        }
-        if ctrl {
-            state.insert(ModifiersState::CONTROL);
         }
-        if alt {
-            state.insert(ModifiersState::ALT);
+        let sides = [
+            (VirtualKey::LeftShift, ModifiersKeys::LSHIFT, ModifiersState::SHIFT),
+            (VirtualKey::RightShift, ModifiersKeys::RSHIFT, ModifiersState::SHIFT),
+            (VirtualKey::LeftControl, ModifiersKeys::LCONTROL, ModifiersState::CONTROL),
+            (VirtualKey::RightControl, ModifiersKeys::RCONTROL, ModifiersState::CONTROL),
+            (VirtualKey::LeftMenu, ModifiersKeys::LALT, ModifiersState::ALT),
+            (VirtualKey::RightMenu, ModifiersKeys::RALT, ModifiersState::ALT),
+            (VirtualKey::LeftWindows, ModifiersKeys::LMETA, ModifiersState::META),
+            (VirtualKey::RightWindows, ModifiersKeys::RMETA, ModifiersState::META),
+        ];
+        let mut state = ModifiersState::empty();
+        let mut pressed_mods = ModifiersKeys::empty();
+        for (virtual_key, key, modifier) in sides {
+            if key_down(window.as_ref(), virtual_key) {
+                pressed_mods.insert(key);
+                state.insert(modifier);
+            }
         }
-        if meta {
-            state.insert(ModifiersState::META);
+        // The generic keys also cover synthesized input that doesn't report a side.
+        for (virtual_key, modifier) in [
+            (VirtualKey::Shift, ModifiersState::SHIFT),
+            (VirtualKey::Control, ModifiersState::CONTROL),
+            (VirtualKey::Menu, ModifiersState::ALT),
+        ] {
+            if key_down(window.as_ref(), virtual_key) {
+                state.insert(modifier);
+            }
         }
-        Modifiers::new(state, ModifiersKeys::empty())
+        Modifiers::new(state, pressed_mods)
     }
 
     fn pointer_details(
         &self,
+        window_id: WindowId,
         point: &windows::UI::Input::PointerPoint,
     ) -> (
         PhysicalPosition<f64>,
@@ -722,7 +2744,7 @@ impl Runner {
     ) {
         let position = point.Position().unwrap_or_default();
         let logical = LogicalPosition::new(position.X as f64, position.Y as f64);
-        let physical = logical.to_physical::<f64>(self.scale_factor());
+        let physical = logical.to_physical::<f64>(self.scale_factor(window_id));
         let primary = point.Properties().ok().and_then(|p| p.IsPrimary().ok()).unwrap_or(true);
 
         let source = match point.PointerDevice().ok().and_then(|d| d.PointerDeviceType().ok()) {
@@ -735,7 +2757,7 @@ impl Runner {
             },
             Some(PointerDeviceType::Pen) => winit_core::event::PointerSource::TabletTool {
                 kind: winit_core::event::TabletToolKind::Pen,
-                data: winit_core::event::TabletToolData::default(),
+                data: point.Properties().map(|props| tablet_tool_data(&props)).unwrap_or_default(),
             },
             _ => winit_core::event::PointerSource::Unknown,
         };
@@ -743,25 +2765,15 @@ impl Runner {
         (physical, primary, source, kind)
     }
 
-    fn run_loop(self: &Arc<Self>) {
+    fn run_loop<A: ApplicationHandler>(self: &Arc<Self>, mut app: A) {
+        // The loop owns the handler, nothing else can reach it while it's borrowed. Reentrant calls
+        // into `ActiveEventLoop` and WinRT callbacks fired during dispatch only queue events,
+        // which `dispatch_events` delivers afterwards.
         let active = ActiveEventLoop { runner: Arc::clone(self) };
         let mut start_cause = StartCause::Init;
 
         loop {
-            let app_ptr = self.app_ptr();
-            if let Some(app_ptr) = app_ptr {
-                unsafe { (&mut *app_ptr).new_events(&active, start_cause) };
-                if start_cause == StartCause::Init {
-                    unsafe { (&mut *app_ptr).can_create_surfaces(&active) };
-                }
-            }
-
-            self.process_os_events();
-            self.dispatch_events(&active);
-
-            if let Some(app_ptr) = app_ptr {
-                unsafe { (&mut *app_ptr).about_to_wait(&active) };
-            }
+            self.run_iteration(&active, &mut app, start_cause, None);
 
             if self.exit.load(Ordering::SeqCst) {
                 break;
@@ -770,58 +2782,238 @@ impl Runner {
             start_cause = next_start_cause(*self.control_flow.lock().unwrap());
         }
 
-        if let Some(app_ptr) = self.take_app() {
-            unsafe {
-                drop(Box::from_raw(app_ptr));
-            }
+        // Dropped on the thread of the loop, while the windows can still be cleaned up.
+        drop(app);
+        self.stop_vblank_thread();
+    }
+
+    fn pump_events(
+        &self,
+        active: &ActiveEventLoop,
+        timeout: Option<Duration>,
+        app: &mut dyn ApplicationHandler,
+    ) -> PumpStatus {
+        let start_cause = if self.pump_started.swap(true, Ordering::SeqCst) {
+            next_start_cause(*self.control_flow.lock().unwrap())
+        } else {
+            StartCause::Init
+        };
+
+        self.run_iteration(active, app, start_cause, timeout);
+        self.resume_panic();
+
+        if self.exit.swap(false, Ordering::SeqCst) {
+            // Allow pumping the event loop again from the start.
+            self.pump_started.store(false, Ordering::SeqCst);
+            PumpStatus::Exit(self.exit_code.swap(0, Ordering::SeqCst))
+        } else {
+            PumpStatus::Continue
         }
     }
 
-    fn process_os_events(&self) {
-        let Some(dispatcher) = self.dispatcher() else {
-            return;
+    fn run_iteration(
+        &self,
+        active: &ActiveEventLoop,
+        app: &mut dyn ApplicationHandler,
+        start_cause: StartCause,
+        timeout: Option<Duration>,
+    ) {
+        app.new_events(active, start_cause);
+        if start_cause == StartCause::Init {
+            app.can_create_surfaces(active);
+        }
+
+        self.process_os_events(timeout);
+        self.dispatch_events(active, app);
+        self.dispatch_redraws(active, app);
+
+        app.about_to_wait(active);
+    }
+
+    /// Returns how long to wait for events, `None` to wait until woken up.
+    fn wait_duration(&self, timeout: Option<Duration>) -> Option<Duration> {
+        // Don't block when the application asked to exit, e.g. from `new_events`.
+        let control_flow = if self.exit.load(Ordering::SeqCst) {
+            ControlFlow::Poll
+        } else {
+            *self.control_flow.lock().unwrap()
         };
-        let control_flow = *self.control_flow.lock().unwrap();
-        match control_flow {
-            ControlFlow::Poll => {
-                let _ = dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessAllIfPresent);
-            },
-            ControlFlow::Wait => {
-                let _ = dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessOneAndAllPending);
-            },
+        let wait = match control_flow {
+            ControlFlow::Poll => Some(Duration::ZERO),
+            ControlFlow::Wait => None,
             ControlFlow::WaitUntil(instant) => {
-                let now = std::time::Instant::now();
-                if now < instant {
-                    let duration = instant - now;
-                    std::thread::sleep(duration);
-                }
-                let _ = dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessAllIfPresent);
+                Some(instant.saturating_duration_since(std::time::Instant::now()))
             },
+        };
+        match (wait, timeout) {
+            (Some(wait), Some(timeout)) => Some(wait.min(timeout)),
+            (wait, timeout) => wait.or(timeout),
         }
     }
 
-    fn dispatch_events(&self, active: &ActiveEventLoop) {
-        let mut queue = VecDeque::new();
-        {
-            let mut lock = self.events.lock().unwrap();
-            std::mem::swap(&mut *lock, &mut queue);
+    /// Waits until `wake_up` is called, without a view there are no OS events to process.
+    fn wait_headless(&self, wait: Option<Duration>) {
+        *self.headless_thread.lock().unwrap() = Some(std::thread::current());
+        self.waiting.store(true, Ordering::SeqCst);
+        if self.queued_events.load(Ordering::SeqCst) == 0 && !self.redraws_ready() {
+            match wait {
+                Some(wait) => std::thread::park_timeout(wait),
+                None => std::thread::park(),
+            }
+        }
+        self.waiting.store(false, Ordering::SeqCst);
+    }
+
+    /// Waits for window messages, including the ones `DispatcherQueue` posts for `wake_up`, and
+    /// dispatches them.
+    fn wait_messages(&self, wait: Option<Duration>) {
+        self.waiting.store(true, Ordering::SeqCst);
+        if self.queued_events.load(Ordering::SeqCst) == 0 && !self.redraws_ready() {
+            app_window::wait_for_messages(wait);
         }
-        let app_ptr = self.app_ptr();
-        let Some(app_ptr) = app_ptr else {
+        self.waiting.store(false, Ordering::SeqCst);
+        if !app_window::dispatch_messages() {
+            // `WM_QUIT` ends the message loop of the application.
+            self.exit.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Processes the dispatcher events, waiting at most `timeout` or as long as the control flow
+    /// asks for, whichever is shorter.
+    fn process_os_events(&self, timeout: Option<Duration>) {
+        let wait = self.wait_duration(timeout);
+        let Some(dispatcher) = self.dispatcher() else {
+            if self.headless {
+                self.wait_headless(wait);
+            } else if self.app_sdk {
+                self.wait_messages(wait);
+            }
             return;
         };
+        if wait.is_some_and(|wait| wait.is_zero()) {
+            let _ = dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessAllIfPresent);
+            return;
+        }
 
-        for event in queue {
-            unsafe {
-                match event {
-                    Event::Window { window_id, event } => {
-                        (&mut *app_ptr).window_event(active, window_id, event)
-                    },
-                    Event::WakeUp => {
-                        self.wakeup_pending.store(false, Ordering::SeqCst);
-                        (&mut *app_ptr).proxy_wake_up(active)
+        // `CoreDispatcher` can't wait for events with a timeout, so a timer stops the wait once
+        // the deadline is reached.
+        let timer = match wait {
+            Some(wait) => {
+                let handler = TimerElapsedHandler::new({
+                    let dispatcher = dispatcher.clone();
+                    let priority = self.dispatcher_priority;
+                    move |_| {
+                        stop_processing_events(&dispatcher, priority);
+                        Ok(())
+                    }
+                });
+                match ThreadPoolTimer::CreateTimer(&handler, wait.into()) {
+                    Ok(timer) => Some(timer),
+                    Err(_) => {
+                        std::thread::sleep(wait);
+                        let _ =
+                            dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessAllIfPresent);
+                        return;
                     },
                 }
+            },
+            None => None,
+        };
+
+        // `ProcessOneAndAllPending` returns after any event, including the ones that don't lead to
+        // an application callback. Block until `wake_up` is called instead, which happens whenever
+        // an event is queued.
+        self.waiting.store(true, Ordering::SeqCst);
+        if self.queued_events.load(Ordering::SeqCst) == 0 && !self.redraws_ready() {
+            let _ = dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessUntilQuit);
+        }
+        self.waiting.store(false, Ordering::SeqCst);
+
+        if let Some(timer) = timer {
+            let _ = timer.Cancel();
+        }
+    }
+
+    /// Delivers the redraws requested until now, after all other events, like on Windows.
+    fn dispatch_redraws(&self, active: &ActiveEventLoop, app: &mut dyn ApplicationHandler) {
+        if self.redraws_throttled() && !self.vblank.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        self.frame_pending.store(false, Ordering::SeqCst);
+        let redraw_requests = std::mem::take(&mut *self.redraw_requests.lock().unwrap());
+        for window_id in redraw_requests {
+            app.window_event(active, window_id, WindowEvent::RedrawRequested);
+        }
+    }
+
+    fn dispatch_events(&self, active: &ActiveEventLoop, app: &mut dyn ApplicationHandler) {
+        self.flush_received_text();
+        // Events queued by the handlers below are delivered in the next iteration.
+        let queue: Vec<Event> = self.event_receiver.lock().unwrap().try_iter().collect();
+        self.queued_events.fetch_sub(queue.len(), Ordering::SeqCst);
+
+        for event in queue {
+            match event {
+                Event::Window { window_id, event, timestamp } => {
+                    *self.event_timestamp.lock().unwrap() = timestamp;
+                    app.window_event(active, window_id, event);
+                    *self.event_timestamp.lock().unwrap() = None;
+                },
+                Event::ScaleFactorChanged { window_id, scale_factor, surface_size } => {
+                    let surface_size_writer = SurfaceSizeWriter::new(Arc::downgrade(&surface_size));
+                    app.window_event(
+                        active,
+                        window_id,
+                        WindowEvent::ScaleFactorChanged { scale_factor, surface_size_writer },
+                    );
+                    let written = *surface_size.lock().unwrap();
+                    self.apply_written_surface_size(window_id, written);
+                },
+                Event::Device { device_id, event } => app.device_event(active, device_id, event),
+                Event::WakeUp => {
+                    self.wakeup_pending.store(false, Ordering::SeqCst);
+                    app.proxy_wake_up(active)
+                },
+                Event::Suspended(deferral) => {
+                    app.suspended(active);
+                    if let Some(deferral) = deferral {
+                        let _ = deferral.Complete();
+                    }
+                },
+                Event::Resumed => app.resumed(active),
+                Event::DestroySurfaces(deferral) => {
+                    app.destroy_surfaces(active);
+                    if let Some(deferral) = deferral {
+                        let _ = deferral.Complete();
+                    }
+                },
+                Event::CanCreateSurfaces => app.can_create_surfaces(active),
+                Event::CloseRequested { window_id, args, deferral } => {
+                    app.window_event(active, window_id, WindowEvent::CloseRequested);
+                    // Dropping the window or exiting accepts the request, otherwise the view stays
+                    // open.
+                    if self.window_created.load(Ordering::SeqCst)
+                        && !self.exit.load(Ordering::SeqCst)
+                    {
+                        let _ = args.SetHandled(true);
+                    }
+                    if let Some(deferral) = deferral {
+                        let _ = deferral.Complete();
+                    }
+                },
+                Event::HolographicCameraAdded { window_id, camera_id, deferral } => {
+                    if let Some(handler) = app.winrt_handler() {
+                        handler.holographic_camera_added(active, window_id, camera_id);
+                    }
+                    if let Some(deferral) = deferral {
+                        let _ = deferral.Complete();
+                    }
+                },
+                Event::WinRt(event) => {
+                    if let Some(handler) = app.winrt_handler() {
+                        dispatch_winrt_event(handler, active, event);
+                    }
+                },
             }
         }
     }
@@ -855,14 +3047,21 @@ impl IFrameworkViewSource_Impl for FrameworkViewSource {
 
 #[allow(non_snake_case)]
 impl IFrameworkView_Impl for FrameworkViewSource {
-    fn Initialize(&self, _application_view: Option<&CoreApplicationView>) -> WinResult<()> {
+    fn Initialize(&self, application_view: Option<&CoreApplicationView>) -> WinResult<()> {
+        if let Some(view) = application_view {
+            self.runner.register_activation_handler(view);
+        }
+        self.runner.register_lifecycle_handlers();
+        self.runner.apply_preferred_launch_mode();
         Ok(())
     }
 
     fn SetWindow(&self, window: Option<&WinRtCoreWindow>) -> WinResult<()> {
         if let Some(window) = window {
             self.runner.set_window(window.clone());
-            let _ = window.Activate();
+            if !self.runner.deferred_activation {
+                let _ = window.Activate();
+            }
         }
         Ok(())
     }
@@ -872,7 +3071,14 @@ impl IFrameworkView_Impl for FrameworkViewSource {
     }
 
     fn Run(&self) -> WinResult<()> {
-        self.runner.run_loop();
+        // Unwinding into the OS aborts the process, so a panic of the application is stored and
+        // resumed once `CoreApplication::Run` returned.
+        let Some(main) = self.runner.take_view_main() else {
+            return Ok(());
+        };
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(main)) {
+            self.runner.store_panic(payload);
+        }
         Ok(())
     }
 
@@ -881,8 +3087,172 @@ impl IFrameworkView_Impl for FrameworkViewSource {
     }
 }
 
-fn dpi_to_scale_factor(dpi: f64) -> f64 {
-    dpi / 96.0
+fn dispatch_winrt_event(
+    handler: &mut dyn ApplicationHandlerExtWinRt,
+    active: &ActiveEventLoop,
+    event: WinRtEvent,
+) {
+    match event {
+        WinRtEvent::LayoutDirectionChanged { right_to_left } => {
+            handler.layout_direction_changed(active, right_to_left)
+        },
+        WinRtEvent::InputPaneOcclusionChanged { window_id, position, size } => {
+            handler.input_pane_occlusion_changed(active, window_id, position, size)
+        },
+        WinRtEvent::TitleBarLayoutChanged { window_id } => {
+            handler.title_bar_layout_changed(active, window_id)
+        },
+        WinRtEvent::AnimationsEnabledChanged { enabled } => {
+            handler.animations_enabled_changed(active, enabled)
+        },
+        WinRtEvent::AdvancedColorInfoChanged { window_id, sdr_white_level } => {
+            handler.advanced_color_info_changed(active, window_id, sdr_white_level)
+        },
+        WinRtEvent::ColorProfileChanged { window_id } => {
+            handler.color_profile_changed(active, window_id)
+        },
+        WinRtEvent::SurfaceRotationChanged { window_id } => {
+            handler.surface_rotation_changed(active, window_id)
+        },
+        WinRtEvent::LaunchActivated(launch) => {
+            handler.launch_activated(active, &launch.arguments, &launch.tile_id)
+        },
+        WinRtEvent::WindowActivated { window_id, pointer_activated } => {
+            handler.window_activated(active, window_id, pointer_activated)
+        },
+        WinRtEvent::InputLanguageChanged { language } => {
+            handler.input_language_changed(active, &language)
+        },
+        WinRtEvent::ClipboardContentChanged => handler.clipboard_content_changed(active),
+        WinRtEvent::HolographicCameraRemoved { window_id, camera_id } => {
+            handler.holographic_camera_removed(active, window_id, camera_id)
+        },
+        WinRtEvent::SpatialInput { window_id, event } => {
+            handler.spatial_input(active, window_id, event)
+        },
+        WinRtEvent::ResizeStarted { window_id } => handler.resize_started(active, window_id),
+        WinRtEvent::ResizeCompleted { window_id } => handler.resize_completed(active, window_id),
+    }
+}
+
+/// `MouseWheelDelta` of one notch of a mouse wheel.
+const WHEEL_DELTA: i32 = 120;
+/// Logical pixels per scrolled line, so that a notch scrolls 100 pixels with the default of 3
+/// lines, as in web browsers.
+const WHEEL_LINE_PIXELS: f64 = 100.0 / 3.0;
+/// The pause between wheel events after which the wheel scroll setting is read again.
+const WHEEL_BURST_GAP: Duration = Duration::from_millis(500);
+
+/// HID usages of the digitizer page, used to check which pen properties the device reports.
+const HID_USAGE_PAGE_DIGITIZER: u32 = 0x0d;
+const HID_USAGE_DIGITIZER_TIP_PRESSURE: u32 = 0x30;
+const HID_USAGE_DIGITIZER_X_TILT: u32 = 0x3d;
+const HID_USAGE_DIGITIZER_Y_TILT: u32 = 0x3e;
+const HID_USAGE_DIGITIZER_TWIST: u32 = 0x41;
+
+fn tablet_tool_data(props: &PointerPointProperties) -> winit_core::event::TabletToolData {
+    let has_usage = |usage| props.HasUsage(HID_USAGE_PAGE_DIGITIZER, usage).unwrap_or(false);
+    let mut data = winit_core::event::TabletToolData::default();
+    if has_usage(HID_USAGE_DIGITIZER_TIP_PRESSURE) {
+        data.force = props
+            .Pressure()
+            .ok()
+            .map(|pressure| winit_core::event::Force::Normalized(pressure as f64));
+    }
+    if has_usage(HID_USAGE_DIGITIZER_X_TILT) || has_usage(HID_USAGE_DIGITIZER_Y_TILT) {
+        let x = props.XTilt().unwrap_or(0.0);
+        let y = props.YTilt().unwrap_or(0.0);
+        data.tilt = Some(winit_core::event::TabletToolTilt {
+            x: x.clamp(-90.0, 90.0) as i8,
+            y: y.clamp(-90.0, 90.0) as i8,
+        });
+    }
+    if has_usage(HID_USAGE_DIGITIZER_TWIST) {
+        data.twist = props.Twist().ok().map(|twist| twist.rem_euclid(360.0) as u16);
+    }
+    data
+}
+
+/// Returns a stable ID for the device of a pointer.
+///
+/// Pens are identified by `PenDevice::PenId`. Other digitizers have no ID, so they are told apart
+/// by their properties: the physical size of the digitizer, the number of contacts it tracks and
+/// the screen area it's mapped to, which differ between e.g. a touch screen and an external touch
+/// display. The ID of a digitizer changes if the screen area it's mapped to does.
+///
+/// Windows merges all mice into a single pointer device, see [`mouse_device_id`].
+fn pointer_device_id(point: &PointerPoint) -> Option<DeviceId> {
+    let device = point.PointerDevice().ok()?;
+    let device_type = device.PointerDeviceType().ok()?;
+    match device_type {
+        PointerDeviceType::Mouse => return Some(mouse_device_id()),
+        PointerDeviceType::Pen => {
+            let pen_id =
+                point.PointerId().and_then(PenDevice::GetFromPointerId).and_then(|pen| pen.PenId());
+            if let Ok(pen_id) = pen_id {
+                return Some(hashed_device_id(pen_id.to_u128()));
+            }
+        },
+        _ => (),
+    }
+    let rect_bits = |rect: Rect| [rect.X, rect.Y, rect.Width, rect.Height].map(f32::to_bits);
+    Some(hashed_device_id((
+        device_type.0,
+        device.IsIntegrated().unwrap_or(false),
+        device.MaxContacts().unwrap_or(0),
+        device.PhysicalDeviceRect().map(rect_bits).unwrap_or_default(),
+        device.ScreenRect().map(rect_bits).unwrap_or_default(),
+    )))
+}
+
+/// Returns the ID of the mouse.
+///
+/// Windows reports all mice as one pointer device, and `MouseDevice` doesn't tell them apart
+/// either, so pointer and device events of every mouse share this ID.
+pub(crate) fn mouse_device_id() -> DeviceId {
+    hashed_device_id(PointerDeviceType::Mouse.0)
+}
+
+/// Returns the ID of the keyboard from the `DeviceId` of a key event, which is empty for
+/// synthesized input.
+fn key_device_id(device_id: &HSTRING) -> Option<DeviceId> {
+    (!device_id.is_empty()).then(|| hashed_device_id(device_id.as_wide()))
+}
+
+fn hashed_device_id(value: impl Hash) -> DeviceId {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    DeviceId::from_raw(hasher.finish() as i64)
+}
+
+fn point_timestamp(point: &windows::UI::Input::PointerPoint) -> Option<Duration> {
+    point.Timestamp().ok().map(Duration::from_micros)
+}
+
+/// Returns the exact scale factor of a view.
+///
+/// `LogicalDpi` is rounded to whole DPI values, so prefer `RawPixelsPerViewPixel` and fall back
+/// to the `ResolutionScale` bucket on systems that don't report it.
+fn scale_factor_for(info: &DisplayInformation) -> f64 {
+    if let Some(scale) = info.RawPixelsPerViewPixel().ok().filter(|&scale| scale > 0.0) {
+        return scale;
+    }
+    match info.ResolutionScale() {
+        Ok(scale) if scale != ResolutionScale::Invalid => scale.0 as f64 / 100.0,
+        _ => info.LogicalDpi().map(|dpi| dpi as f64 / 96.0).unwrap_or(1.0),
+    }
+}
+
+/// Stops `CoreDispatcher::ProcessEvents` from another thread.
+fn stop_processing_events(dispatcher: &CoreDispatcher, priority: CoreDispatcherPriority) {
+    // `StopProcessEvents` has to be called from the thread that processes the events.
+    let _ = dispatcher.RunAsync(
+        priority,
+        &DispatchedHandler::new({
+            let dispatcher = dispatcher.clone();
+            move || dispatcher.StopProcessEvents()
+        }),
+    );
 }
 
 fn next_start_cause(control_flow: ControlFlow) -> StartCause {
@@ -900,8 +3270,77 @@ fn next_start_cause(control_flow: ControlFlow) -> StartCause {
     }
 }
 
-fn key_down(window: &WinRtCoreWindow, key: VirtualKey) -> bool {
-    window.GetKeyState(key).map(|state| state.contains(CoreVirtualKeyStates::Down)).unwrap_or(false)
+fn key_down(window: Option<&WinRtCoreWindow>, key: VirtualKey) -> bool {
+    match window {
+        Some(window) => window
+            .GetKeyState(key)
+            .map(|state| state.contains(CoreVirtualKeyStates::Down))
+            .unwrap_or(false),
+        // The window of a `DispatcherQueue` event loop is a Win32 window, whose virtual keys are
+        // the same.
+        None => key_state_down(key.0),
+    }
+}
+
+/// Returns the scancode of a key, extended keys carry the `0xe0` prefix like the scancodes of the
+/// Win32 backend.
+fn key_scancode(status: &CorePhysicalKeyStatus) -> u16 {
+    status.ScanCode as u16 | if status.IsExtendedKey { 0xe000 } else { 0 }
+}
+
+/// Distinguishes the left, right and numpad variants of a key, like the Win32 backend.
+///
+/// `CoreWindow` reports generic virtual keys for Shift, Control and Alt, so the sides are told
+/// apart by the scan code and the extended flag instead.
+fn key_location(virtual_key: VirtualKey, status: &CorePhysicalKeyStatus) -> KeyLocation {
+    const SCANCODE_RIGHT_SHIFT: u32 = 0x36;
+
+    let extended = status.IsExtendedKey;
+    match virtual_key {
+        VirtualKey::Shift if status.ScanCode == SCANCODE_RIGHT_SHIFT => KeyLocation::Right,
+        VirtualKey::Control | VirtualKey::Menu if extended => KeyLocation::Right,
+        VirtualKey::Shift | VirtualKey::Control | VirtualKey::Menu => KeyLocation::Left,
+        VirtualKey::LeftShift | VirtualKey::LeftControl | VirtualKey::LeftMenu => KeyLocation::Left,
+        VirtualKey::LeftWindows => KeyLocation::Left,
+        VirtualKey::RightShift | VirtualKey::RightControl | VirtualKey::RightMenu => {
+            KeyLocation::Right
+        },
+        VirtualKey::RightWindows => KeyLocation::Right,
+        VirtualKey::Enter if extended => KeyLocation::Numpad,
+        VirtualKey::Insert
+        | VirtualKey::Delete
+        | VirtualKey::End
+        | VirtualKey::Down
+        | VirtualKey::PageDown
+        | VirtualKey::Left
+        | VirtualKey::Clear
+        | VirtualKey::Right
+        | VirtualKey::Home
+        | VirtualKey::Up
+        | VirtualKey::PageUp => {
+            if extended {
+                KeyLocation::Standard
+            } else {
+                KeyLocation::Numpad
+            }
+        },
+        VirtualKey::NumberPad0
+        | VirtualKey::NumberPad1
+        | VirtualKey::NumberPad2
+        | VirtualKey::NumberPad3
+        | VirtualKey::NumberPad4
+        | VirtualKey::NumberPad5
+        | VirtualKey::NumberPad6
+        | VirtualKey::NumberPad7
+        | VirtualKey::NumberPad8
+        | VirtualKey::NumberPad9
+        | VirtualKey::Decimal
+        | VirtualKey::Divide
+        | VirtualKey::Multiply
+        | VirtualKey::Subtract
+        | VirtualKey::Add => KeyLocation::Numpad,
+        _ => KeyLocation::Standard,
+    }
 }
 
 fn map_key(virtual_key: VirtualKey, modifiers: ModifiersState) -> (Key, Option<SmolStr>) {
@@ -926,77 +3365,221 @@ fn map_key(virtual_key: VirtualKey, modifiers: ModifiersState) -> (Key, Option<S
 
 fn map_virtual_key_named(virtual_key: VirtualKey) -> Option<winit_core::keyboard::NamedKey> {
     use winit_core::keyboard::NamedKey;
-    match virtual_key {
-        VirtualKey::Enter => Some(NamedKey::Enter),
-        VirtualKey::Escape => Some(NamedKey::Escape),
-        VirtualKey::Back => Some(NamedKey::Backspace),
-        VirtualKey::Tab => Some(NamedKey::Tab),
-        VirtualKey::Delete => Some(NamedKey::Delete),
-        VirtualKey::Insert => Some(NamedKey::Insert),
-        VirtualKey::Home => Some(NamedKey::Home),
-        VirtualKey::End => Some(NamedKey::End),
-        VirtualKey::PageUp => Some(NamedKey::PageUp),
-        VirtualKey::PageDown => Some(NamedKey::PageDown),
-        VirtualKey::Left => Some(NamedKey::ArrowLeft),
-        VirtualKey::Right => Some(NamedKey::ArrowRight),
-        VirtualKey::Up => Some(NamedKey::ArrowUp),
-        VirtualKey::Down => Some(NamedKey::ArrowDown),
-        VirtualKey::F1 => Some(NamedKey::F1),
-        VirtualKey::F2 => Some(NamedKey::F2),
-        VirtualKey::F3 => Some(NamedKey::F3),
-        VirtualKey::F4 => Some(NamedKey::F4),
-        VirtualKey::F5 => Some(NamedKey::F5),
-        VirtualKey::F6 => Some(NamedKey::F6),
-        VirtualKey::F7 => Some(NamedKey::F7),
-        VirtualKey::F8 => Some(NamedKey::F8),
-        VirtualKey::F9 => Some(NamedKey::F9),
-        VirtualKey::F10 => Some(NamedKey::F10),
-        VirtualKey::F11 => Some(NamedKey::F11),
-        VirtualKey::F12 => Some(NamedKey::F12),
-        _ => None,
-    }
+    let named = match virtual_key {
+        VirtualKey::Back => NamedKey::Backspace,
+        VirtualKey::Tab => NamedKey::Tab,
+        VirtualKey::Clear => NamedKey::Clear,
+        VirtualKey::Enter => NamedKey::Enter,
+        VirtualKey::Shift | VirtualKey::LeftShift | VirtualKey::RightShift => NamedKey::Shift,
+        VirtualKey::Control | VirtualKey::LeftControl | VirtualKey::RightControl => {
+            NamedKey::Control
+        },
+        VirtualKey::Menu | VirtualKey::LeftMenu | VirtualKey::RightMenu => NamedKey::Alt,
+        VirtualKey::Pause => NamedKey::Pause,
+        VirtualKey::CapitalLock => NamedKey::CapsLock,
+        VirtualKey::Kana => NamedKey::KanaMode,
+        VirtualKey::Junja => NamedKey::JunjaMode,
+        VirtualKey::Final => NamedKey::FinalMode,
+        VirtualKey::Kanji => NamedKey::KanjiMode,
+        VirtualKey::Escape => NamedKey::Escape,
+        VirtualKey::Convert => NamedKey::Convert,
+        VirtualKey::NonConvert => NamedKey::NonConvert,
+        VirtualKey::Accept => NamedKey::Accept,
+        VirtualKey::ModeChange => NamedKey::ModeChange,
+        VirtualKey::PageUp => NamedKey::PageUp,
+        VirtualKey::PageDown => NamedKey::PageDown,
+        VirtualKey::End => NamedKey::End,
+        VirtualKey::Home => NamedKey::Home,
+        VirtualKey::Left => NamedKey::ArrowLeft,
+        VirtualKey::Up => NamedKey::ArrowUp,
+        VirtualKey::Right => NamedKey::ArrowRight,
+        VirtualKey::Down => NamedKey::ArrowDown,
+        VirtualKey::Select => NamedKey::Select,
+        VirtualKey::Print => NamedKey::Print,
+        VirtualKey::Execute => NamedKey::Execute,
+        VirtualKey::Snapshot => NamedKey::PrintScreen,
+        VirtualKey::Insert => NamedKey::Insert,
+        VirtualKey::Delete => NamedKey::Delete,
+        VirtualKey::Help => NamedKey::Help,
+        VirtualKey::LeftWindows | VirtualKey::RightWindows => NamedKey::Meta,
+        VirtualKey::Application => NamedKey::ContextMenu,
+        VirtualKey::Sleep => NamedKey::Standby,
+        VirtualKey::F1 => NamedKey::F1,
+        VirtualKey::F2 => NamedKey::F2,
+        VirtualKey::F3 => NamedKey::F3,
+        VirtualKey::F4 => NamedKey::F4,
+        VirtualKey::F5 => NamedKey::F5,
+        VirtualKey::F6 => NamedKey::F6,
+        VirtualKey::F7 => NamedKey::F7,
+        VirtualKey::F8 => NamedKey::F8,
+        VirtualKey::F9 => NamedKey::F9,
+        VirtualKey::F10 => NamedKey::F10,
+        VirtualKey::F11 => NamedKey::F11,
+        VirtualKey::F12 => NamedKey::F12,
+        VirtualKey::F13 => NamedKey::F13,
+        VirtualKey::F14 => NamedKey::F14,
+        VirtualKey::F15 => NamedKey::F15,
+        VirtualKey::F16 => NamedKey::F16,
+        VirtualKey::F17 => NamedKey::F17,
+        VirtualKey::F18 => NamedKey::F18,
+        VirtualKey::F19 => NamedKey::F19,
+        VirtualKey::F20 => NamedKey::F20,
+        VirtualKey::F21 => NamedKey::F21,
+        VirtualKey::F22 => NamedKey::F22,
+        VirtualKey::F23 => NamedKey::F23,
+        VirtualKey::F24 => NamedKey::F24,
+        VirtualKey::NumberKeyLock => NamedKey::NumLock,
+        VirtualKey::Scroll => NamedKey::ScrollLock,
+        VirtualKey::GoBack => NamedKey::BrowserBack,
+        VirtualKey::GoForward => NamedKey::BrowserForward,
+        VirtualKey::Refresh => NamedKey::BrowserRefresh,
+        VirtualKey::Stop => NamedKey::BrowserStop,
+        VirtualKey::Search => NamedKey::BrowserSearch,
+        VirtualKey::Favorites => NamedKey::BrowserFavorites,
+        VirtualKey::GoHome => NamedKey::BrowserHome,
+        VK_VOLUME_MUTE => NamedKey::AudioVolumeMute,
+        VK_VOLUME_DOWN => NamedKey::AudioVolumeDown,
+        VK_VOLUME_UP => NamedKey::AudioVolumeUp,
+        VK_MEDIA_NEXT_TRACK => NamedKey::MediaTrackNext,
+        VK_MEDIA_PREV_TRACK => NamedKey::MediaTrackPrevious,
+        VK_MEDIA_STOP => NamedKey::MediaStop,
+        VK_MEDIA_PLAY_PAUSE => NamedKey::MediaPlayPause,
+        VK_LAUNCH_MAIL => NamedKey::LaunchMail,
+        VK_LAUNCH_MEDIA_SELECT => NamedKey::LaunchMediaPlayer,
+        VK_LAUNCH_APP1 => NamedKey::LaunchApplication1,
+        VK_LAUNCH_APP2 => NamedKey::LaunchApplication2,
+        VK_PROCESSKEY => NamedKey::Process,
+        VK_ATTN => NamedKey::Attn,
+        VK_CRSEL => NamedKey::CrSel,
+        VK_EXSEL => NamedKey::ExSel,
+        VK_EREOF => NamedKey::EraseEof,
+        VK_PLAY => NamedKey::Play,
+        VK_ZOOM => NamedKey::ZoomToggle,
+        _ => return None,
+    };
+    Some(named)
 }
 
+/// Maps keys that produce text to their character on the US layout.
+///
+/// The text of the `KeyDown` is replaced by `CharacterReceived`, which respects the active layout.
 fn map_virtual_key_char(virtual_key: VirtualKey, shift: bool) -> Option<char> {
-    match virtual_key {
-        VirtualKey::A => Some(if shift { 'A' } else { 'a' }),
-        VirtualKey::B => Some(if shift { 'B' } else { 'b' }),
-        VirtualKey::C => Some(if shift { 'C' } else { 'c' }),
-        VirtualKey::D => Some(if shift { 'D' } else { 'd' }),
-        VirtualKey::E => Some(if shift { 'E' } else { 'e' }),
-        VirtualKey::F => Some(if shift { 'F' } else { 'f' }),
-        VirtualKey::G => Some(if shift { 'G' } else { 'g' }),
-        VirtualKey::H => Some(if shift { 'H' } else { 'h' }),
-        VirtualKey::I => Some(if shift { 'I' } else { 'i' }),
-        VirtualKey::J => Some(if shift { 'J' } else { 'j' }),
-        VirtualKey::K => Some(if shift { 'K' } else { 'k' }),
-        VirtualKey::L => Some(if shift { 'L' } else { 'l' }),
-        VirtualKey::M => Some(if shift { 'M' } else { 'm' }),
-        VirtualKey::N => Some(if shift { 'N' } else { 'n' }),
-        VirtualKey::O => Some(if shift { 'O' } else { 'o' }),
-        VirtualKey::P => Some(if shift { 'P' } else { 'p' }),
-        VirtualKey::Q => Some(if shift { 'Q' } else { 'q' }),
-        VirtualKey::R => Some(if shift { 'R' } else { 'r' }),
-        VirtualKey::S => Some(if shift { 'S' } else { 's' }),
-        VirtualKey::T => Some(if shift { 'T' } else { 't' }),
-        VirtualKey::U => Some(if shift { 'U' } else { 'u' }),
-        VirtualKey::V => Some(if shift { 'V' } else { 'v' }),
-        VirtualKey::W => Some(if shift { 'W' } else { 'w' }),
-        VirtualKey::X => Some(if shift { 'X' } else { 'x' }),
-        VirtualKey::Y => Some(if shift { 'Y' } else { 'y' }),
-        VirtualKey::Z => Some(if shift { 'Z' } else { 'z' }),
-        VirtualKey::Number0 => Some('0'),
-        VirtualKey::Number1 => Some('1'),
-        VirtualKey::Number2 => Some('2'),
-        VirtualKey::Number3 => Some('3'),
-        VirtualKey::Number4 => Some('4'),
-        VirtualKey::Number5 => Some('5'),
-        VirtualKey::Number6 => Some('6'),
-        VirtualKey::Number7 => Some('7'),
-        VirtualKey::Number8 => Some('8'),
-        VirtualKey::Number9 => Some('9'),
-        VirtualKey::Space => Some(' '),
-        _ => None,
+    let (ch, shifted) = match virtual_key {
+        VirtualKey::Space => (' ', ' '),
+        VirtualKey::A => ('a', 'A'),
+        VirtualKey::B => ('b', 'B'),
+        VirtualKey::C => ('c', 'C'),
+        VirtualKey::D => ('d', 'D'),
+        VirtualKey::E => ('e', 'E'),
+        VirtualKey::F => ('f', 'F'),
+        VirtualKey::G => ('g', 'G'),
+        VirtualKey::H => ('h', 'H'),
+        VirtualKey::I => ('i', 'I'),
+        VirtualKey::J => ('j', 'J'),
+        VirtualKey::K => ('k', 'K'),
+        VirtualKey::L => ('l', 'L'),
+        VirtualKey::M => ('m', 'M'),
+        VirtualKey::N => ('n', 'N'),
+        VirtualKey::O => ('o', 'O'),
+        VirtualKey::P => ('p', 'P'),
+        VirtualKey::Q => ('q', 'Q'),
+        VirtualKey::R => ('r', 'R'),
+        VirtualKey::S => ('s', 'S'),
+        VirtualKey::T => ('t', 'T'),
+        VirtualKey::U => ('u', 'U'),
+        VirtualKey::V => ('v', 'V'),
+        VirtualKey::W => ('w', 'W'),
+        VirtualKey::X => ('x', 'X'),
+        VirtualKey::Y => ('y', 'Y'),
+        VirtualKey::Z => ('z', 'Z'),
+        VirtualKey::Number0 => ('0', ')'),
+        VirtualKey::Number1 => ('1', '!'),
+        VirtualKey::Number2 => ('2', '@'),
+        VirtualKey::Number3 => ('3', '#'),
+        VirtualKey::Number4 => ('4', '$'),
+        VirtualKey::Number5 => ('5', '%'),
+        VirtualKey::Number6 => ('6', '^'),
+        VirtualKey::Number7 => ('7', '&'),
+        VirtualKey::Number8 => ('8', '*'),
+        VirtualKey::Number9 => ('9', '('),
+        VirtualKey::NumberPad0 => ('0', '0'),
+        VirtualKey::NumberPad1 => ('1', '1'),
+        VirtualKey::NumberPad2 => ('2', '2'),
+        VirtualKey::NumberPad3 => ('3', '3'),
+        VirtualKey::NumberPad4 => ('4', '4'),
+        VirtualKey::NumberPad5 => ('5', '5'),
+        VirtualKey::NumberPad6 => ('6', '6'),
+        VirtualKey::NumberPad7 => ('7', '7'),
+        VirtualKey::NumberPad8 => ('8', '8'),
+        VirtualKey::NumberPad9 => ('9', '9'),
+        VirtualKey::Multiply => ('*', '*'),
+        VirtualKey::Add => ('+', '+'),
+        VirtualKey::Separator => (',', ','),
+        VirtualKey::Subtract => ('-', '-'),
+        VirtualKey::Decimal => ('.', '.'),
+        VirtualKey::Divide => ('/', '/'),
+        VK_OEM_1 => (';', ':'),
+        VK_OEM_PLUS => ('=', '+'),
+        VK_OEM_COMMA => (',', '<'),
+        VK_OEM_MINUS => ('-', '_'),
+        VK_OEM_PERIOD => ('.', '>'),
+        VK_OEM_2 => ('/', '?'),
+        VK_OEM_3 => ('`', '~'),
+        VK_OEM_4 => ('[', '{'),
+        VK_OEM_5 => ('\\', '|'),
+        VK_OEM_6 => (']', '}'),
+        VK_OEM_7 => ('\'', '"'),
+        VK_OEM_102 => ('<', '>'),
+        _ => return None,
+    };
+    Some(if shift { shifted } else { ch })
+}
+
+// Virtual keys without a `VirtualKey` constant, see `winuser.h`.
+const VK_VOLUME_MUTE: VirtualKey = VirtualKey(0xad);
+const VK_VOLUME_DOWN: VirtualKey = VirtualKey(0xae);
+const VK_VOLUME_UP: VirtualKey = VirtualKey(0xaf);
+const VK_MEDIA_NEXT_TRACK: VirtualKey = VirtualKey(0xb0);
+const VK_MEDIA_PREV_TRACK: VirtualKey = VirtualKey(0xb1);
+const VK_MEDIA_STOP: VirtualKey = VirtualKey(0xb2);
+const VK_MEDIA_PLAY_PAUSE: VirtualKey = VirtualKey(0xb3);
+const VK_LAUNCH_MAIL: VirtualKey = VirtualKey(0xb4);
+const VK_LAUNCH_MEDIA_SELECT: VirtualKey = VirtualKey(0xb5);
+const VK_LAUNCH_APP1: VirtualKey = VirtualKey(0xb6);
+const VK_LAUNCH_APP2: VirtualKey = VirtualKey(0xb7);
+const VK_OEM_1: VirtualKey = VirtualKey(0xba);
+const VK_OEM_PLUS: VirtualKey = VirtualKey(0xbb);
+const VK_OEM_COMMA: VirtualKey = VirtualKey(0xbc);
+const VK_OEM_MINUS: VirtualKey = VirtualKey(0xbd);
+const VK_OEM_PERIOD: VirtualKey = VirtualKey(0xbe);
+const VK_OEM_2: VirtualKey = VirtualKey(0xbf);
+const VK_OEM_3: VirtualKey = VirtualKey(0xc0);
+const VK_OEM_4: VirtualKey = VirtualKey(0xdb);
+const VK_OEM_5: VirtualKey = VirtualKey(0xdc);
+const VK_OEM_6: VirtualKey = VirtualKey(0xdd);
+const VK_OEM_7: VirtualKey = VirtualKey(0xde);
+const VK_OEM_102: VirtualKey = VirtualKey(0xe2);
+const VK_PROCESSKEY: VirtualKey = VirtualKey(0xe5);
+const VK_ATTN: VirtualKey = VirtualKey(0xf6);
+const VK_CRSEL: VirtualKey = VirtualKey(0xf7);
+const VK_EXSEL: VirtualKey = VirtualKey(0xf8);
+const VK_EREOF: VirtualKey = VirtualKey(0xf9);
+const VK_PLAY: VirtualKey = VirtualKey(0xfa);
+const VK_ZOOM: VirtualKey = VirtualKey(0xfb);
+
+/// Compares buttons while ignoring the data that may change between press and release.
+fn is_same_button(a: &ButtonSource, b: &ButtonSource) -> bool {
+    match (a, b) {
+        (ButtonSource::Mouse(a), ButtonSource::Mouse(b)) => a == b,
+        (ButtonSource::Touch { finger_id: a, .. }, ButtonSource::Touch { finger_id: b, .. }) => {
+            a == b
+        },
+        (
+            ButtonSource::TabletTool { kind: a_kind, button: a_button, .. },
+            ButtonSource::TabletTool { kind: b_kind, button: b_button, .. },
+        ) => a_kind == b_kind && a_button == b_button,
+        (ButtonSource::Unknown(a), ButtonSource::Unknown(b)) => a == b,
+        _ => false,
     }
 }
 
@@ -1004,7 +3587,7 @@ fn button_source_from_point(
     props: Option<&PointerPointProperties>,
     source: &winit_core::event::PointerSource,
 ) -> winit_core::event::ButtonSource {
-    use winit_core::event::{ButtonSource, TabletToolButton, TabletToolData, TabletToolKind};
+    use winit_core::event::{TabletToolButton, TabletToolKind};
     match source {
         winit_core::event::PointerSource::Mouse => {
             let update = props.and_then(|p| p.PointerUpdateKind().ok());
@@ -1026,11 +3609,224 @@ fn button_source_from_point(
         winit_core::event::PointerSource::Touch { finger_id, .. } => {
             ButtonSource::Touch { finger_id: *finger_id, force: None }
         },
-        winit_core::event::PointerSource::TabletTool { .. } => ButtonSource::TabletTool {
+        winit_core::event::PointerSource::TabletTool { data, .. } => ButtonSource::TabletTool {
             kind: TabletToolKind::Pen,
             button: TabletToolButton::Contact,
-            data: TabletToolData::default(),
+            data: data.clone(),
         },
         winit_core::event::PointerSource::Unknown => ButtonSource::Unknown(0),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use winit_core::keyboard::NamedKey;
+
+    use super::*;
+    use crate::WindowExtWinRt;
+
+    fn status(scancode: u32, extended: bool) -> CorePhysicalKeyStatus {
+        CorePhysicalKeyStatus { ScanCode: scancode, IsExtendedKey: extended, ..Default::default() }
+    }
+
+    #[test]
+    fn map_virtual_key_named_table() {
+        let table = [
+            (VirtualKey::Back, Some(NamedKey::Backspace)),
+            (VirtualKey::Enter, Some(NamedKey::Enter)),
+            (VirtualKey::LeftShift, Some(NamedKey::Shift)),
+            (VirtualKey::RightControl, Some(NamedKey::Control)),
+            (VirtualKey::Menu, Some(NamedKey::Alt)),
+            (VirtualKey::Escape, Some(NamedKey::Escape)),
+            (VirtualKey::Left, Some(NamedKey::ArrowLeft)),
+            (VirtualKey::Snapshot, Some(NamedKey::PrintScreen)),
+            (VirtualKey::RightWindows, Some(NamedKey::Meta)),
+            (VirtualKey::Application, Some(NamedKey::ContextMenu)),
+            (VirtualKey::F1, Some(NamedKey::F1)),
+            (VK_VOLUME_UP, Some(NamedKey::AudioVolumeUp)),
+            (VK_MEDIA_PLAY_PAUSE, Some(NamedKey::MediaPlayPause)),
+            (VirtualKey::A, None),
+            (VirtualKey::Number1, None),
+            (VK_OEM_1, None),
+        ];
+        for (virtual_key, expected) in table {
+            assert_eq!(map_virtual_key_named(virtual_key), expected, "{virtual_key:?}");
+        }
+    }
+
+    #[test]
+    fn map_virtual_key_char_table() {
+        let table = [
+            (VirtualKey::Space, Some(' '), Some(' ')),
+            (VirtualKey::A, Some('a'), Some('A')),
+            (VirtualKey::Z, Some('z'), Some('Z')),
+            (VirtualKey::Number2, Some('2'), Some('@')),
+            (VirtualKey::NumberPad7, Some('7'), Some('7')),
+            (VirtualKey::Divide, Some('/'), Some('/')),
+            (VK_OEM_1, Some(';'), Some(':')),
+            (VK_OEM_3, Some('`'), Some('~')),
+            (VK_OEM_7, Some('\''), Some('"')),
+            (VK_OEM_102, Some('<'), Some('>')),
+            (VirtualKey::Enter, None, None),
+            (VirtualKey::F1, None, None),
+        ];
+        for (virtual_key, unshifted, shifted) in table {
+            assert_eq!(map_virtual_key_char(virtual_key, false), unshifted, "{virtual_key:?}");
+            assert_eq!(map_virtual_key_char(virtual_key, true), shifted, "{virtual_key:?}");
+        }
+    }
+
+    #[test]
+    fn map_key_table() {
+        let table = [
+            (VirtualKey::A, ModifiersState::empty(), Key::Character("a".into()), Some("a")),
+            (VirtualKey::A, ModifiersState::SHIFT, Key::Character("A".into()), Some("A")),
+            (VirtualKey::Enter, ModifiersState::empty(), Key::Named(NamedKey::Enter), Some("\r")),
+            (VirtualKey::Tab, ModifiersState::empty(), Key::Named(NamedKey::Tab), Some("\t")),
+            (
+                VirtualKey::Escape,
+                ModifiersState::empty(),
+                Key::Named(NamedKey::Escape),
+                Some("\u{1b}"),
+            ),
+            (
+                VirtualKey(0xe8),
+                ModifiersState::empty(),
+                Key::Unidentified(winit_core::keyboard::NativeKey::Windows(0xe8)),
+                None,
+            ),
+        ];
+        for (virtual_key, modifiers, key, text) in table {
+            let (mapped_key, mapped_text) = map_key(virtual_key, modifiers);
+            assert_eq!(mapped_key, key, "{virtual_key:?}");
+            assert_eq!(mapped_text.as_deref(), text, "{virtual_key:?}");
+        }
+    }
+
+    #[test]
+    fn key_scancode_table() {
+        let table = [
+            (status(0x1e, false), 0x1e),
+            (status(0x1d, false), 0x1d),
+            // Right Ctrl, right Alt and numpad Enter are the extended variants.
+            (status(0x1d, true), 0xe01d),
+            (status(0x38, true), 0xe038),
+            (status(0x1c, true), 0xe01c),
+            (status(0x48, true), 0xe048),
+            (status(0x48, false), 0x48),
+        ];
+        for (status, expected) in table {
+            let scancode = key_scancode(&status);
+            assert_eq!(scancode, expected, "{status:?}");
+            let physical_key = crate::scancode_to_physicalkey(u32::from(scancode));
+            assert_eq!(
+                physical_key,
+                PhysicalKey::Unidentified(NativeKeyCode::Windows(expected)),
+                "{status:?}"
+            );
+            assert_eq!(crate::physicalkey_to_scancode(physical_key), Some(u32::from(expected)));
+        }
+    }
+
+    #[test]
+    fn key_location_table() {
+        let table = [
+            (VirtualKey::Shift, status(0x2a, false), KeyLocation::Left),
+            (VirtualKey::Shift, status(0x36, false), KeyLocation::Right),
+            (VirtualKey::Control, status(0x1d, false), KeyLocation::Left),
+            (VirtualKey::Control, status(0x1d, true), KeyLocation::Right),
+            (VirtualKey::Menu, status(0x38, true), KeyLocation::Right),
+            (VirtualKey::LeftWindows, status(0x5b, true), KeyLocation::Left),
+            (VirtualKey::RightWindows, status(0x5c, true), KeyLocation::Right),
+            (VirtualKey::Enter, status(0x1c, false), KeyLocation::Standard),
+            (VirtualKey::Enter, status(0x1c, true), KeyLocation::Numpad),
+            (VirtualKey::Home, status(0x47, false), KeyLocation::Numpad),
+            (VirtualKey::Home, status(0x47, true), KeyLocation::Standard),
+            (VirtualKey::NumberPad5, status(0x4c, false), KeyLocation::Numpad),
+            (VirtualKey::Divide, status(0x35, true), KeyLocation::Numpad),
+            (VirtualKey::A, status(0x1e, false), KeyLocation::Standard),
+        ];
+        for (virtual_key, status, expected) in table {
+            assert_eq!(key_location(virtual_key, &status), expected, "{virtual_key:?}");
+        }
+    }
+
+    #[test]
+    fn received_text_surrogate_pair() {
+        let mut received = ReceivedText::default();
+        // U+1F600, split across two `CharacterReceived` events.
+        assert!(!received.push_code(0xd83d));
+        assert!(received.push_code(0xde00));
+        assert_eq!(received.text, "\u{1f600}");
+        assert_eq!(received.high_surrogate, None);
+
+        // A lone low surrogate is dropped.
+        let mut received = ReceivedText::default();
+        assert!(!received.push_code(0xde00));
+        assert_eq!(received.text, "");
+
+        // A high surrogate followed by a regular character is dropped.
+        let mut received = ReceivedText::default();
+        assert!(!received.push_code(0xd83d));
+        assert!(received.push_code(u32::from('a')));
+        assert_eq!(received.text, "a");
+        assert_eq!(received.high_surrogate, None);
+    }
+
+    /// Records the events of a headless event loop, which exits once the proxy woke it up.
+    struct HeadlessApp {
+        events: Arc<Mutex<Vec<&'static str>>>,
+        window: Option<Box<dyn CoreWindowTrait>>,
+    }
+
+    impl ApplicationHandler for HeadlessApp {
+        fn can_create_surfaces(&mut self, event_loop: &dyn RootActiveEventLoop) {
+            self.events.lock().unwrap().push("can_create_surfaces");
+            let window = event_loop.create_window(WindowAttributes::default()).unwrap();
+            assert_eq!(window.surface_size(), PhysicalSize::new(640, 480));
+            assert_eq!(window.scale_factor(), 1.5);
+            assert!(window.core_window().is_none());
+            window.request_redraw();
+            self.window = Some(window);
+        }
+
+        fn window_event(
+            &mut self,
+            event_loop: &dyn RootActiveEventLoop,
+            window_id: WindowId,
+            event: WindowEvent,
+        ) {
+            assert_eq!(Some(window_id), self.window.as_ref().map(|window| window.id()));
+            if let WindowEvent::RedrawRequested = event {
+                self.events.lock().unwrap().push("redraw_requested");
+                // Woken up from another thread, like a proxy is usually used.
+                let proxy = event_loop.create_proxy();
+                std::thread::spawn(move || proxy.wake_up()).join().unwrap();
+            }
+        }
+
+        fn proxy_wake_up(&mut self, event_loop: &dyn RootActiveEventLoop) {
+            self.events.lock().unwrap().push("proxy_wake_up");
+            event_loop.exit();
+        }
+    }
+
+    #[test]
+    fn headless_event_loop_delivers_events() {
+        let mut attributes = PlatformSpecificEventLoopAttributes {
+            headless: Some(HeadlessSurface {
+                size: PhysicalSize::new(640, 480),
+                scale_factor: 1.5,
+            }),
+            ..Default::default()
+        };
+        let event_loop = EventLoop::new(&mut attributes).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let app = HeadlessApp { events: Arc::clone(&events), window: None };
+        event_loop.run_app(app).unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["can_create_surfaces", "redraw_requested", "proxy_wake_up"]
+        );
+    }
+}
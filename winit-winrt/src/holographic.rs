@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use tracing::warn;
+use windows::core::{AgileReference, Result as WinResult};
+use windows::Foundation::TypedEventHandler;
+use windows::Graphics::Holographic::{
+    HolographicSpace, HolographicSpaceCameraAddedEventArgs, HolographicSpaceCameraRemovedEventArgs,
+};
+use windows::UI::Core::CoreWindow as WinRtCoreWindow;
+use windows::UI::Input::Spatial::{
+    SpatialInteractionManager, SpatialInteractionPressKind, SpatialInteractionSourceEventArgs,
+    SpatialInteractionSourceKind,
+};
+use winit_core::application::winrt::{SpatialInputEvent, SpatialPress, SpatialSourceKind};
+use winit_core::event::ElementState;
+use winit_core::window::WindowId;
+
+use crate::event_loop::{Event, Runner, WinRtEvent};
+
+/// Creates the `HolographicSpace` of the window, see `EventLoopBuilderExtWinRt::with_holographic`.
+///
+/// This must be called before the window is activated.
+pub(crate) fn create_holographic_space(
+    runner: &Arc<Runner>,
+    window: &WinRtCoreWindow,
+    window_id: WindowId,
+) -> Option<AgileReference<HolographicSpace>> {
+    let result = HolographicSpace::CreateForCoreWindow(window).and_then(|space| {
+        register_camera_handlers(runner, &space, window_id)?;
+        AgileReference::new(&space)
+    });
+    match result {
+        Ok(space) => Some(space),
+        Err(err) => {
+            warn!("failed to create the holographic space: {err}");
+            None
+        },
+    }
+}
+
+fn register_camera_handlers(
+    runner: &Arc<Runner>,
+    space: &HolographicSpace,
+    window_id: WindowId,
+) -> WinResult<()> {
+    space.CameraAdded(&TypedEventHandler::<
+        HolographicSpace,
+        HolographicSpaceCameraAddedEventArgs,
+    >::new({
+        let runner = Arc::clone(runner);
+        move |_, args| {
+            runner.catch_panic(|| {
+                if let Some(args) = args {
+                    let camera = args.Camera()?;
+                    let camera_id = camera.Id()?;
+                    runner.insert_holographic_camera(camera_id, &camera);
+                    // The camera isn't used for rendering before the application created its
+                    // resources, so the deferral is held until it handled the event.
+                    let deferral = args.GetDeferral().ok();
+                    runner.queue_event(Event::HolographicCameraAdded {
+                        window_id,
+                        camera_id,
+                        deferral,
+                    });
+                }
+                Ok(())
+            })
+        }
+    }))?;
+
+    space.CameraRemoved(&TypedEventHandler::<
+        HolographicSpace,
+        HolographicSpaceCameraRemovedEventArgs,
+    >::new({
+        let runner = Arc::clone(runner);
+        move |_, args| {
+            runner.catch_panic(|| {
+                if let Some(args) = args {
+                    let camera_id = args.Camera()?.Id()?;
+                    runner.remove_holographic_camera(camera_id);
+                    runner.queue_event(Event::WinRt(WinRtEvent::HolographicCameraRemoved {
+                        window_id,
+                        camera_id,
+                    }));
+                }
+                Ok(())
+            })
+        }
+    }))?;
+    Ok(())
+}
+
+/// Reports the spatial interaction sources of the view as `WinRtEvent::SpatialInput`.
+pub(crate) fn register_spatial_input_handlers(runner: &Arc<Runner>, window_id: WindowId) {
+    let Ok(manager) = SpatialInteractionManager::GetForCurrentView() else {
+        return;
+    };
+    let handler =
+        |to_event: fn(&SpatialInteractionSourceEventArgs) -> WinResult<SpatialInputEvent>| {
+            let runner = Arc::clone(runner);
+            TypedEventHandler::<SpatialInteractionManager, SpatialInteractionSourceEventArgs>::new(
+                move |_, args| {
+                    runner.catch_panic(|| {
+                        if let Some(args) = args {
+                            let event = to_event(args)?;
+                            runner.queue_event(Event::WinRt(WinRtEvent::SpatialInput {
+                                window_id,
+                                event,
+                            }));
+                        }
+                        Ok(())
+                    })
+                },
+            )
+        };
+
+    let _ = manager.SourceDetected(&handler(|args| {
+        let source = args.State()?.Source()?;
+        Ok(SpatialInputEvent::SourceDetected {
+            source_id: source.Id()?,
+            kind: source_kind(source.Kind()?),
+        })
+    }));
+    let _ = manager.SourceLost(&handler(|args| {
+        Ok(SpatialInputEvent::SourceLost { source_id: args.State()?.Source()?.Id()? })
+    }));
+    let _ = manager.SourcePressed(&handler(|args| button_event(args, ElementState::Pressed)));
+    let _ = manager.SourceReleased(&handler(|args| button_event(args, ElementState::Released)));
+}
+
+fn button_event(
+    args: &SpatialInteractionSourceEventArgs,
+    state: ElementState,
+) -> WinResult<SpatialInputEvent> {
+    Ok(SpatialInputEvent::Button {
+        source_id: args.State()?.Source()?.Id()?,
+        press: press_kind(args.PressKind().unwrap_or(SpatialInteractionPressKind::None)),
+        state,
+    })
+}
+
+fn source_kind(kind: SpatialInteractionSourceKind) -> SpatialSourceKind {
+    match kind {
+        SpatialInteractionSourceKind::Hand => SpatialSourceKind::Hand,
+        SpatialInteractionSourceKind::Voice => SpatialSourceKind::Voice,
+        SpatialInteractionSourceKind::Controller => SpatialSourceKind::Controller,
+        _ => SpatialSourceKind::Other,
+    }
+}
+
+/// `PressKind` was added in Windows 10, version 1607; older versions only report selections.
+fn press_kind(kind: SpatialInteractionPressKind) -> SpatialPress {
+    match kind {
+        SpatialInteractionPressKind::None | SpatialInteractionPressKind::Select => {
+            SpatialPress::Select
+        },
+        SpatialInteractionPressKind::Menu => SpatialPress::Menu,
+        SpatialInteractionPressKind::Grasp => SpatialPress::Grasp,
+        SpatialInteractionPressKind::Touchpad => SpatialPress::Touchpad,
+        SpatialInteractionPressKind::Thumbstick => SpatialPress::Thumbstick,
+        _ => SpatialPress::Other,
+    }
+}
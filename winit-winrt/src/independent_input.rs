@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use tracing::warn;
+use windows::core::{AgileReference, IInspectable, Interface, Result as WinResult};
+use windows::Foundation::Numerics::Vector2;
+use windows::Foundation::TypedEventHandler;
+use windows::UI::Composition::Visual;
+use windows::UI::Core::{
+    CoreIndependentInputSource, CoreIndependentInputSourceController, CoreInputDeviceTypes,
+    CoreProcessEventsOption, PointerEventArgs,
+};
+use winit_core::event::ElementState;
+use winit_core::window::WindowId;
+
+use crate::event_loop::Runner;
+use crate::util::ensure_winrt_initialized;
+use crate::InputDevices;
+
+/// Delivers the pointer input of `devices` on a dedicated thread, see
+/// `EventLoopBuilderExtWinRt::with_independent_input`.
+///
+/// This must be called on the view thread, which owns the composition target.
+pub(crate) fn start_input_thread(runner: &Arc<Runner>, window_id: WindowId, devices: InputDevices) {
+    let visual = match input_visual(runner) {
+        Ok(visual) => visual,
+        Err(err) => {
+            warn!("failed to create the independent input visual: {err}");
+            return;
+        },
+    };
+    let runner = Arc::clone(runner);
+    let _ = std::thread::Builder::new().name("winit input".into()).spawn(move || {
+        ensure_winrt_initialized();
+        // The input source raises its events on the thread it was created on, while its
+        // dispatcher processes them.
+        let result = visual.resolve().and_then(|visual| {
+            let controller = CoreIndependentInputSourceController::CreateForVisual(&visual)?;
+            controller.SetControlledInput(device_types(devices))?;
+            // Other devices still reach the `CoreWindow`.
+            controller.SetIsTransparentForUncontrolledInput(true)?;
+            let source = controller.Source()?;
+            register_pointer_handlers(&source, &runner, window_id)?;
+            source.Dispatcher()?.ProcessEvents(CoreProcessEventsOption::ProcessUntilQuit)?;
+            controller.Close()
+        });
+        if let Err(err) = result {
+            warn!("independent input stopped: {err}");
+        }
+    });
+}
+
+/// Returns a visual covering the whole window, the root of the composition target.
+fn input_visual(runner: &Runner) -> WinResult<AgileReference<Visual>> {
+    let target = runner.composition_target()?;
+    let visual = target.Compositor()?.CreateContainerVisual()?;
+    visual.SetRelativeSizeAdjustment(Vector2 { X: 1.0, Y: 1.0 })?;
+    target.SetRoot(&visual)?;
+    AgileReference::new(&visual.cast::<Visual>()?)
+}
+
+fn device_types(devices: InputDevices) -> CoreInputDeviceTypes {
+    let mut types = CoreInputDeviceTypes::None;
+    if devices.contains(InputDevices::MOUSE) {
+        types |= CoreInputDeviceTypes::Mouse;
+    }
+    if devices.contains(InputDevices::TOUCH) {
+        types |= CoreInputDeviceTypes::Touch;
+    }
+    if devices.contains(InputDevices::PEN) {
+        types |= CoreInputDeviceTypes::Pen;
+    }
+    types
+}
+
+/// Delivers the pointer input of `source`, which also serves `SwapChainPanel` hosting.
+pub(crate) fn register_pointer_handlers(
+    source: &CoreIndependentInputSource,
+    runner: &Arc<Runner>,
+    window_id: WindowId,
+) -> WinResult<()> {
+    let handler = |handle: fn(&Runner, WindowId, &PointerEventArgs)| {
+        let runner = Arc::clone(runner);
+        TypedEventHandler::<IInspectable, PointerEventArgs>::new(move |_, args| {
+            runner.catch_panic(|| {
+                if let Some(args) = args {
+                    handle(&runner, window_id, args);
+                }
+                Ok(())
+            })
+        })
+    };
+
+    source.PointerMoved(&handler(Runner::handle_pointer_moved))?;
+    source.PointerPressed(&handler(|runner, window_id, args| {
+        runner.handle_pointer_button(window_id, args, ElementState::Pressed)
+    }))?;
+    source.PointerReleased(&handler(|runner, window_id, args| {
+        runner.handle_pointer_button(window_id, args, ElementState::Released)
+    }))?;
+    source.PointerEntered(&handler(Runner::handle_pointer_entered))?;
+    source.PointerExited(&handler(Runner::handle_pointer_exited))?;
+    source.PointerCaptureLost(&handler(Runner::handle_pointer_capture_lost))?;
+    source.PointerWheelChanged(&handler(Runner::handle_pointer_wheel))?;
+    Ok(())
+}
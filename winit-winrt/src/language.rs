@@ -0,0 +1,20 @@
+use windows::Globalization::{ApplicationLanguages, Language, LanguageLayoutDirection};
+
+use crate::util::ensure_winrt_initialized;
+
+/// Whether the primary application language is laid out right-to-left.
+pub(crate) fn layout_is_right_to_left() -> bool {
+    ensure_winrt_initialized();
+    let Some(tag) =
+        ApplicationLanguages::Languages().ok().and_then(|languages| languages.GetAt(0).ok())
+    else {
+        return false;
+    };
+    Language::CreateLanguage(&tag)
+        .and_then(|language| language.LayoutDirection())
+        .map(|direction| {
+            direction == LanguageLayoutDirection::Rtl
+                || direction == LanguageLayoutDirection::TtbRtl
+        })
+        .unwrap_or(false)
+}
@@ -1,28 +1,74 @@
 //! WinRT/UWP backend for winit.
-#![cfg(all(target_os = "windows", __WINRT__))]
+#![cfg(all(target_os = "windows", any(__WINRT__, feature = "winrt")))]
 #![allow(non_snake_case)]
 
 #[cfg(target_env = "msvc")]
 compile_error!(
-    "WinRT backend requires the GNU toolchain. Use target x86_64-pc-windows-gnu with \
-     cfg(__WINRT__)."
+    "WinRT backend requires the GNU toolchain. Use target x86_64-pc-windows-gnu with the \
+     `winrt` feature of winit."
 );
 
+/// Wraps an error of the OS, usually a `windows::core::Error` carrying the `HRESULT` and its
+/// message, which is the [`source`] of the returned [`OsError`].
+///
+/// [`source`]: std::error::Error::source
+/// [`OsError`]: winit_core::error::OsError
+macro_rules! os_error {
+    ($error:expr) => {{ winit_core::error::OsError::new(line!(), file!(), $error) }};
+}
+
+mod app_window;
+mod attention;
+mod capabilities;
+mod clipboard;
 mod cursor;
+mod drag_drop;
+mod dxgi;
+mod entry_point;
 mod event_loop;
+mod holographic;
+mod independent_input;
+mod language;
 mod monitor;
+mod orientation;
+mod panel;
+mod theme;
+mod title_bar;
 mod util;
 mod window;
 
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroIsize;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+pub use capabilities::{MouseCapabilities, TouchCapabilities};
 pub use event_loop::{ActiveEventLoop, EventLoop, PlatformSpecificEventLoopAttributes};
 pub use monitor::MonitorHandle;
 pub use window::Window;
+pub use winit_core::application::winrt::ApplicationHandlerExtWinRt;
+use dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use tracing::warn;
+use windows::core::{AgileReference, IInspectable};
+use windows::Graphics::Holographic::{HolographicCamera, HolographicSpace};
+use windows::UI::Composition::CompositionTarget;
 use windows::UI::Core::{CoreDispatcher, CoreWindow as WinRtCoreWindow};
+use windows::UI::ViewManagement::ApplicationView;
+use winit_core::cursor::{CustomCursor, CustomCursorSource};
+use winit_core::error::RequestError;
 use winit_core::event_loop::ActiveEventLoop as CoreActiveEventLoop;
 use winit_core::keyboard::{NativeKeyCode, PhysicalKey};
-use winit_core::window::Window as CoreWindow;
+use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
+use winit_core::window::{PlatformWindowAttributes, Window as CoreWindow};
+
+use crate::cursor::WinRtCustomCursor;
+
+// Used by `main!`, which expands in the crate of the application.
+#[doc(hidden)]
+pub use tracing as __tracing;
 
 /// Compatibility enum for Windows backdrop requests.
 ///
@@ -40,7 +86,7 @@ pub enum BackdropType {
 
 /// Compatibility color type for Windows titlebar/border customization requests.
 ///
-/// On WinRT/UWP this value is accepted but ignored.
+/// On WinRT/UWP this is only used for the title bar colors, border colors are ignored.
 #[repr(transparent)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
@@ -74,16 +120,755 @@ pub enum CornerPreference {
     RoundSmall = 3,
 }
 
+/// The raw visible region of a window, see [`WindowExtWinRt::visible_bounds`].
+///
+/// The rectangle is expressed in the same coordinate space as `CoreWindow::Bounds`, i.e. it is
+/// not relative to the window's surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibleBounds {
+    /// The origin of the visible region, in logical pixels.
+    pub logical_position: LogicalPosition<f64>,
+    /// The size of the visible region, in logical pixels.
+    pub logical_size: LogicalSize<f64>,
+    /// The origin of the visible region, in physical pixels.
+    pub physical_position: PhysicalPosition<i32>,
+    /// The size of the visible region, in physical pixels.
+    pub physical_size: PhysicalSize<u32>,
+}
+
+/// The metrics of the system title bar, see [`WindowExtWinRt::title_bar_metrics`].
+///
+/// All values are in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TitleBarMetrics {
+    /// The width reserved by the system on the left, e.g. for the caption buttons in
+    /// right-to-left layouts.
+    pub left_inset: u32,
+    /// The width reserved by the system on the right, usually for the caption buttons.
+    pub right_inset: u32,
+    /// The height of the title bar.
+    pub height: u32,
+}
+
+/// Statistics about the frames recently presented on the display, see
+/// [`WindowExtWinRt::frame_statistics`].
+///
+/// The counts correspond to `DXGI_FRAME_STATISTICS`. Comparing them between two calls tells how
+/// many refreshes passed and whether frames were missed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameStatistics {
+    /// The number of frames presented.
+    pub present_count: u32,
+    /// The refresh count of the display when the last frame was presented.
+    pub present_refresh_count: u32,
+    /// The refresh count of the display at `sync_time`.
+    pub sync_refresh_count: u32,
+    /// The time of the last vertical blank, relative to system boot like
+    /// [`EventLoopExtWinRt::event_timestamp`].
+    pub sync_time: Duration,
+}
+
+/// The kind of color the display is currently using, see [`AdvancedColorInfo`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum AdvancedColorKind {
+    /// Standard dynamic range, i.e. sRGB.
+    #[default]
+    StandardDynamicRange,
+    /// Wide color gamut, without extended luminance.
+    WideColorGamut,
+    /// High dynamic range.
+    HighDynamicRange,
+}
+
+/// The advanced color capabilities of a display, see
+/// [`MonitorHandleExtWinRt::advanced_color_info`].
+///
+/// Luminance values are in nits and are `0.0` when the display doesn't report them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdvancedColorInfo {
+    /// The kind of color the display is currently using.
+    pub kind: AdvancedColorKind,
+    /// Whether the display can be switched to [`AdvancedColorKind::HighDynamicRange`].
+    pub hdr_supported: bool,
+    /// The peak luminance of the display.
+    pub max_luminance: f32,
+    /// The minimum luminance of the display.
+    pub min_luminance: f32,
+    /// The maximum luminance the display can sustain over the full frame.
+    pub max_average_full_frame_luminance: f32,
+    /// The luminance that SDR content is mapped to, i.e. what `1.0` in an scRGB swapchain
+    /// corresponds to.
+    pub sdr_white_level: f32,
+}
+
+bitflags::bitflags! {
+    /// A set of display orientations, see [`EventLoopExtWinRt::set_auto_rotation_preferences`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct Orientations: u8 {
+        const LANDSCAPE         = 1 << 0;
+        const PORTRAIT          = 1 << 1;
+        const LANDSCAPE_FLIPPED = 1 << 2;
+        const PORTRAIT_FLIPPED  = 1 << 3;
+    }
+}
+
+bitflags::bitflags! {
+    /// A set of pointer device kinds, see [`EventLoopBuilderExtWinRt::with_independent_input`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct InputDevices: u8 {
+        const MOUSE = 1 << 0;
+        const TOUCH = 1 << 1;
+        const PEN   = 1 << 2;
+    }
+}
+
+/// The rotation of the display relative to its native orientation, see
+/// [`WindowExtWinRt::surface_rotation`].
+///
+/// The variants correspond to `DXGI_MODE_ROTATION`, and can be passed to
+/// `IDXGISwapChain1::SetRotation` to pre-rotate frames.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum SurfaceRotation {
+    /// The display is in its native orientation.
+    #[default]
+    Identity,
+    /// The content has to be rotated by 90 degrees clockwise.
+    Rotate90,
+    /// The content has to be rotated by 180 degrees.
+    Rotate180,
+    /// The content has to be rotated by 270 degrees clockwise.
+    Rotate270,
+}
+
+/// The surface a headless event loop reports, see [`EventLoopBuilderExtWinRt::with_headless`].
+#[derive(Debug, Clone, Copy)]
+pub struct HeadlessSurface {
+    /// The size reported by [`Window::surface_size`].
+    ///
+    /// [`Window::surface_size`]: winit_core::window::Window::surface_size
+    pub size: PhysicalSize<u32>,
+    /// The scale factor reported by [`Window::scale_factor`].
+    ///
+    /// [`Window::scale_factor`]: winit_core::window::Window::scale_factor
+    pub scale_factor: f64,
+}
+
+impl PartialEq for HeadlessSurface {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.scale_factor.to_bits() == other.scale_factor.to_bits()
+    }
+}
+
+impl Eq for HeadlessSurface {}
+
+impl Hash for HeadlessSurface {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.scale_factor.to_bits().hash(state);
+    }
+}
+
+/// The arguments the application was launched with, see [`EventLoopExtWinRt::launch_arguments`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct LaunchArguments {
+    /// The arguments passed to the application, e.g. from a secondary tile or the command line.
+    pub arguments: String,
+    /// The ID of the tile the application was launched from, `"App"` for the primary tile.
+    pub tile_id: String,
+}
+
+/// Data dragged out of a window, see [`WindowExtWinRt::start_drag`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DragData {
+    /// Plain text.
+    Text(String),
+    /// Files and folders. The application must be able to access them, e.g. because they are in
+    /// its local folder.
+    Paths(Vec<PathBuf>),
+}
+
+/// Window attributes that are specific to WinRT/UWP, passed to
+/// [`WindowAttributes::with_platform_attributes`].
+///
+/// [`WindowAttributes::with_platform_attributes`]: winit_core::window::WindowAttributes::with_platform_attributes
+#[derive(Clone, Default)]
+pub struct WindowAttributesWinRt {
+    pub(crate) swap_chain_panel: Option<AgileReference<IInspectable>>,
+    pub(crate) app_window: Option<NonZeroIsize>,
+}
+
+impl std::fmt::Debug for WindowAttributesWinRt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowAttributesWinRt")
+            .field("swap_chain_panel", &self.swap_chain_panel.is_some())
+            .field("app_window", &self.app_window)
+            .finish()
+    }
+}
+
+impl WindowAttributesWinRt {
+    /// Hosts the window in a XAML `SwapChainPanel` instead of the whole `CoreWindow`.
+    ///
+    /// `panel` is the `Windows.UI.Xaml.Controls.SwapChainPanel`, as an `IInspectable` because
+    /// the `windows` crate doesn't project `Windows.UI.Xaml`. The event loop must be attached to
+    /// the view of the XAML application, see
+    /// [`EventLoopBuilderExtWinRt::with_attach_to_current_view`], and the window must be created
+    /// on its thread.
+    ///
+    /// The surface size and the scale factor then follow `SizeChanged` and
+    /// `CompositionScaleChanged` of the panel, and the pointer input over the panel is delivered
+    /// in its coordinates from a `CoreIndependentInputSource`. Keyboard input still comes from
+    /// the `CoreWindow`. The window handle carries the panel, see [`Window`]'s
+    /// `HasWindowHandle` implementation, and renderers can also take it from
+    /// [`WindowExtWinRt::swap_chain_panel`] to set a composition swapchain on it through
+    /// `ISwapChainPanelNative`.
+    pub fn with_swap_chain_panel(mut self, panel: &IInspectable) -> Self {
+        self.swap_chain_panel = match AgileReference::new(panel) {
+            Ok(panel) => Some(panel),
+            Err(err) => {
+                warn!("failed to reference the SwapChainPanel: {err}");
+                None
+            },
+        };
+        self
+    }
+
+    /// Makes the window the Windows App SDK `AppWindow` with the given `HWND`, which is required
+    /// when the event loop runs on a `DispatcherQueue`, see
+    /// [`EventLoopBuilderExtWinRt::with_dispatcher_queue`].
+    ///
+    /// `hwnd` is the `HWND` of the `Microsoft.UI.Windowing.AppWindow`, e.g. from
+    /// `Win32Interop::GetWindowFromWindowId(app_window.Id())`, because the `windows` crate doesn't
+    /// project `Microsoft.UI`. The window must be created on the thread of the `AppWindow`.
+    ///
+    /// Winit subclasses the window to report its surface size, scale factor, focus, keyboard and
+    /// mouse input, and to turn `WM_CLOSE` into [`WindowEvent::CloseRequested`]. The application
+    /// keeps owning the `AppWindow`: it shows, presents and destroys it. The window handle is a
+    /// Win32 handle of the `HWND`.
+    ///
+    /// [`WindowEvent::CloseRequested`]: winit_core::event::WindowEvent::CloseRequested
+    pub fn with_app_window(mut self, hwnd: NonZeroIsize) -> Self {
+        self.app_window = Some(hwnd);
+        self
+    }
+}
+
+impl PlatformWindowAttributes for WindowAttributesWinRt {
+    fn box_clone(&self) -> Box<dyn PlatformWindowAttributes> {
+        Box::from(self.clone())
+    }
+}
+
 /// Additional methods on [`ActiveEventLoop`] that are specific to WinRT/UWP.
 pub trait EventLoopExtWinRt {
     /// Returns the `CoreDispatcher` associated with the current view, if available.
     fn dispatcher(&self) -> Option<CoreDispatcher>;
+
+    /// Runs `f` on the view thread the next time its `CoreDispatcher` processes events.
+    ///
+    /// Many WinRT APIs can only be called on the view thread; this queues the closure with
+    /// `CoreDispatcher::RunAsync` at the priority set with
+    /// [`EventLoopBuilderExtWinRt::with_dispatcher_priority`]. A panic in `f` ends the event
+    /// loop and is resumed by `EventLoop::run_app`. Returns an error in headless mode, where
+    /// there is no dispatcher.
+    fn run_on_dispatcher<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<(), RequestError>;
+
+    /// Like [`EventLoopExtWinRt::run_on_dispatcher`], but the result of `f` is sent through the
+    /// returned channel.
+    ///
+    /// Receiving from the channel on the view thread itself blocks forever, since `f` can't run
+    /// until the view thread processes events again. The channel is disconnected if `f` never
+    /// runs, e.g. because the application exited first.
+    fn run_on_dispatcher_with_result<T, F>(&self, f: F) -> Result<mpsc::Receiver<T>, RequestError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static;
+
+    /// Returns the mouse capabilities of the device.
+    ///
+    /// Useful for adapting the UI, e.g. hiding hover-only affordances when no mouse is present.
+    fn mouse_capabilities(&self) -> MouseCapabilities;
+
+    /// Returns the touch capabilities of the device.
+    fn touch_capabilities(&self) -> TouchCapabilities;
+
+    /// Returns whether the effective layout direction of the application is right-to-left.
+    ///
+    /// This is derived from the primary application language. Changes are reported through
+    /// [`ApplicationHandlerExtWinRt::layout_direction_changed`].
+    fn is_layout_right_to_left(&self) -> bool;
+
+    /// Returns the OS timestamp of the input event currently being dispatched.
+    ///
+    /// This is only available while handling a pointer or keyboard event inside
+    /// [`ApplicationHandler::window_event`]. Pointer timestamps are taken from
+    /// `PointerPoint::Timestamp`; `KeyEventArgs` carries no timestamp, so keys are timestamped
+    /// when the backend receives them. The timestamp is relative to system boot, so only
+    /// differences between timestamps are meaningful, e.g. to measure latency or gesture velocity.
+    ///
+    /// [`ApplicationHandler::window_event`]: winit_core::application::ApplicationHandler::window_event
+    fn event_timestamp(&self) -> Option<Duration>;
+
+    /// Maps a [`CustomCursorSource`] to a cursor resource in the application package.
+    ///
+    /// UWP cannot create cursors from image data at runtime, so
+    /// [`ActiveEventLoop::create_custom_cursor`] looks up the resource registered for the
+    /// given source and returns an error if there is none. The resource ID refers to a cursor
+    /// compiled into the application's resource file.
+    ///
+    /// [`ActiveEventLoop::create_custom_cursor`]: winit_core::event_loop::ActiveEventLoop::create_custom_cursor
+    fn register_cursor_resource(&self, source: CustomCursorSource, resource_id: u32);
+
+    /// Returns whether the user allows animations in the user interface.
+    ///
+    /// This is `false` when the user asked for reduced motion in the accessibility settings, see
+    /// `UISettings::AnimationsEnabled`. Changes are reported through
+    /// [`ApplicationHandlerExtWinRt::animations_enabled_changed`].
+    fn animations_enabled(&self) -> bool;
+
+    /// Like [`ActiveEventLoop::exit`], but the process exits with the given status code.
+    ///
+    /// [`ActiveEventLoop::exit`]: winit_core::event_loop::ActiveEventLoop::exit
+    fn exit_with_code(&self, code: i32);
+
+    /// Restricts the orientations the system rotates the application to, e.g. to lock a game to
+    /// landscape.
+    ///
+    /// An empty set lets the system rotate freely again. This is only a preference: it is ignored
+    /// on devices without an orientation sensor, and when the device is docked. See
+    /// `DisplayInformation::AutoRotationPreferences`.
+    fn set_auto_rotation_preferences(&self, orientations: Orientations);
+
+    /// Returns the arguments of the most recent launch activation.
+    ///
+    /// The activation is processed with the first OS events, so this is `None` during the initial
+    /// [`ApplicationHandler::can_create_surfaces`]; use
+    /// [`ApplicationHandlerExtWinRt::launch_activated`] to be notified instead. Returns `None`
+    /// if the application wasn't activated by a launch, e.g. when it was started through a
+    /// protocol or file association.
+    ///
+    /// [`ApplicationHandler::can_create_surfaces`]: winit_core::application::ApplicationHandler::can_create_surfaces
+    fn launch_arguments(&self) -> Option<LaunchArguments>;
+
+    /// Returns the text on the clipboard, or `None` if the clipboard holds no text.
+    ///
+    /// UWP applications can only read the clipboard while one of their windows is focused.
+    /// Changes are reported through [`ApplicationHandlerExtWinRt::clipboard_content_changed`].
+    fn clipboard_text(&self) -> Option<String>;
+
+    /// Replaces the content of the clipboard with `text`.
+    ///
+    /// Returns `true` on success. Like reading, this requires one of the application's windows to
+    /// be focused.
+    fn set_clipboard_text(&self, text: &str) -> bool;
+}
+
+/// How the application view is sized when the application is launched, see
+/// [`EventLoopBuilderExtWinRt::with_preferred_launch_mode`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum LaunchWindowingMode {
+    /// Let the system pick the size, usually the one the application had when it was last closed.
+    #[default]
+    Auto,
+    /// Launch with the given size.
+    Size(LogicalSize<u32>),
+    /// Launch in full screen.
+    FullScreen,
+}
+
+/// The `CoreDispatcherPriority` the event loop is woken up with, see
+/// [`EventLoopBuilderExtWinRt::with_dispatcher_priority`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum DispatcherPriority {
+    /// Wake up once the dispatcher has no other work pending.
+    Idle,
+    /// Wake up after the pending input and other normal priority work.
+    Low,
+    /// Wake up in the order the wake-ups were scheduled, interleaved with input.
+    #[default]
+    Normal,
+    /// Wake up before the pending input. This priority is meant for the system, so use it with
+    /// care.
+    High,
+}
+
+/// How winit initializes the Windows Runtime on the threads it uses, see
+/// [`EventLoopBuilderExtWinRt::with_apartment`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Apartment {
+    /// Initialize with `RO_INIT_MULTITHREADED`.
+    #[default]
+    MultiThreaded = 0,
+    /// Initialize with `RO_INIT_SINGLETHREADED`.
+    SingleThreaded = 1,
+    /// Don't initialize the Windows Runtime, the host initializes every thread winit is used on.
+    HostManaged = 2,
+}
+
+/// Additional methods on `EventLoopBuilder` that are specific to WinRT/UWP.
+pub trait EventLoopBuilderExtWinRt {
+    /// Whether to deliver every pointer sample coalesced into a single `PointerMoved` callback.
+    ///
+    /// High-frequency devices such as pens report more samples than the UI thread processes
+    /// `CoreWindow::PointerMoved` events. When enabled, each sample returned by
+    /// `PointerEventArgs::GetIntermediatePoints` is delivered as its own
+    /// [`WindowEvent::PointerMoved`], oldest first, which inking applications need to draw
+    /// smooth strokes.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`WindowEvent::PointerMoved`]: winit_core::event::WindowEvent::PointerMoved
+    fn with_coalesced_pointer_history(&mut self, enabled: bool) -> &mut Self;
+
+    /// Sets how the view is sized when the application is launched.
+    ///
+    /// This sets `ApplicationView::PreferredLaunchWindowingMode` and
+    /// `ApplicationView::PreferredLaunchViewSize` before the view is activated, so it already
+    /// applies to the current launch. The preference is persisted by the system and also applies
+    /// to later launches, until it is changed again.
+    ///
+    /// By default, the preference is left untouched.
+    fn with_preferred_launch_mode(&mut self, mode: LaunchWindowingMode) -> &mut Self;
+
+    /// Whether to deliver the system back request as [`WindowEvent::CloseRequested`].
+    ///
+    /// Xbox has no close button, the user backs out of an application with the B button
+    /// instead, which raises `SystemNavigationManager::BackRequested`. When enabled, the request
+    /// is marked as handled and reported as [`WindowEvent::CloseRequested`], so that simple
+    /// applications exit cleanly. Otherwise the system handles the request, which usually sends
+    /// the application to the background.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`WindowEvent::CloseRequested`]: winit_core::event::WindowEvent::CloseRequested
+    fn with_back_requested_as_close(&mut self, enabled: bool) -> &mut Self;
+
+    /// Whether the application can veto closing the view from its title bar.
+    ///
+    /// When enabled, the close button raises `SystemNavigationManagerPreview::CloseRequested`,
+    /// which is reported as [`WindowEvent::CloseRequested`] while the view is still alive. The
+    /// view is closed if the application drops the window or exits the event loop in response,
+    /// otherwise it stays open.
+    ///
+    /// This requires the restricted `confirmAppClose` capability in the package manifest.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`WindowEvent::CloseRequested`]: winit_core::event::WindowEvent::CloseRequested
+    fn with_close_confirmation(&mut self, enabled: bool) -> &mut Self;
+
+    /// Whether to deliver [`WindowEvent::RedrawRequested`] at the vertical blank.
+    ///
+    /// When enabled, redraws requested with [`Window::request_redraw`] are held back until the
+    /// next vertical blank of the primary output, which is waited for on a background thread with
+    /// `IDXGIOutput::WaitForVBlank`. Requesting a redraw in every `RedrawRequested` then paces
+    /// the application to the refresh rate of the display, similar to `requestAnimationFrame` on
+    /// the web. Redraws are delivered right away if the vertical blank can't be waited for.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`WindowEvent::RedrawRequested`]: winit_core::event::WindowEvent::RedrawRequested
+    /// [`Window::request_redraw`]: winit_core::window::Window::request_redraw
+    fn with_vsync_redraws(&mut self, enabled: bool) -> &mut Self;
+
+    /// Runs the event loop without a `CoreApplication` view, e.g. to test an application on a CI
+    /// agent where it can't be packaged.
+    ///
+    /// The event loop runs on the calling thread and the window reports the given surface. No OS
+    /// events are delivered, so the application is driven through
+    /// [`ActiveEventLoop::create_proxy`] and [`Window::request_redraw`]. APIs which need the view
+    /// fail or return defaults, and [`WindowExtWinRt::core_window`] returns `None`.
+    ///
+    /// [`ActiveEventLoop::create_proxy`]: winit_core::event_loop::ActiveEventLoop::create_proxy
+    /// [`Window::request_redraw`]: winit_core::window::Window::request_redraw
+    fn with_headless(&mut self, surface: HeadlessSurface) -> &mut Self;
+
+    /// Sets the priority of the wake-ups of the event loop.
+    ///
+    /// Events queued from other threads, e.g. by an [`EventLoopProxy`], and timeouts of
+    /// [`ControlFlow::WaitUntil`] stop `CoreDispatcher::ProcessEvents` by dispatching a callback
+    /// with this priority. A lower priority lets pending input be processed first, which keeps
+    /// input latency low when the loop is woken up often; a higher priority delivers the wake-ups
+    /// sooner.
+    ///
+    /// [`DispatcherPriority::Normal`] by default.
+    ///
+    /// [`EventLoopProxy`]: winit_core::event_loop::EventLoopProxy
+    /// [`ControlFlow::WaitUntil`]: winit_core::event_loop::ControlFlow::WaitUntil
+    fn with_dispatcher_priority(&mut self, priority: DispatcherPriority) -> &mut Self;
+
+    /// Sets how the Windows Runtime is initialized on the threads winit calls it from.
+    ///
+    /// Initializing a thread which already is in another apartment fails, so hosts which set up a
+    /// single-threaded apartment themselves should pick [`Apartment::SingleThreaded`] or
+    /// [`Apartment::HostManaged`]. The view thread is always set up by `CoreApplication`.
+    ///
+    /// [`Apartment::MultiThreaded`] by default.
+    fn with_apartment(&mut self, apartment: Apartment) -> &mut Self;
+
+    /// Whether to leave the activation of the `CoreWindow` to the application.
+    ///
+    /// The `CoreWindow` is activated as soon as it is created by default, which dismisses the
+    /// splash screen. When deferred, the splash screen stays up until
+    /// [`WindowExtWinRt::activate`] is called, e.g. to show an extended splash screen while the
+    /// application loads its resources. The system terminates applications which don't activate
+    /// their window in time.
+    ///
+    /// Disabled by default.
+    fn with_deferred_activation(&mut self, deferred: bool) -> &mut Self;
+
+    /// Attaches the event loop to the view of the calling thread instead of creating one, e.g. to
+    /// host winit in an existing XAML or C++ application.
+    ///
+    /// The event loop must then be created on a view thread after its `CoreWindow` was set, e.g.
+    /// in `IFrameworkView::Run` of the host, and fails with [`EventLoopError::Os`] otherwise.
+    /// Winit registers its handlers on that `CoreWindow` and processes its dispatcher, but leaves
+    /// `CoreApplication::Run` and the activation of the window to the host. `EventLoop::run_app`
+    /// returns to the host once the application exits.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`EventLoopError::Os`]: winit_core::error::EventLoopError::Os
+    fn with_attach_to_current_view(&mut self, attach: bool) -> &mut Self;
+
+    /// Receives the pointer input of the given devices on a dedicated thread instead of the view
+    /// thread.
+    ///
+    /// The input is received through a `CoreIndependentInputSourceController` for a visual
+    /// covering the window, so it is queued with low latency even while the view thread is busy,
+    /// e.g. for inking. The events are still delivered to the application on the view thread. The
+    /// visual is the root of [`WindowExtWinRt::composition_target`], so visuals of the
+    /// application must be inserted into its children instead of replacing it.
+    ///
+    /// Requires Windows 10, version 1903. Empty by default.
+    fn with_independent_input(&mut self, devices: InputDevices) -> &mut Self;
+
+    /// Creates a `HolographicSpace` for the window, to render to a mixed reality headset such as
+    /// HoloLens.
+    ///
+    /// The space is created before the window is activated and is available through
+    /// [`WindowExtWinRt::holographic_space`]. Frames are then rendered to the holographic cameras
+    /// with `HolographicSpace::CreateNextFrame`, so the window has no raw window handle to create
+    /// a swapchain for. Cameras are reported through
+    /// [`ApplicationHandlerExtWinRt::holographic_camera_added`] and
+    /// [`ApplicationHandlerExtWinRt::holographic_camera_removed`].
+    ///
+    /// Disabled by default.
+    fn with_holographic(&mut self, holographic: bool) -> &mut Self;
+
+    /// Runs the event loop on a `DispatcherQueue` instead of `CoreApplication`, for packaged
+    /// Windows App SDK (WinUI 3) applications.
+    ///
+    /// The `DispatcherQueue` of the calling thread is used, or one is created for it. Winit then
+    /// runs the message loop of the thread, so `EventLoop::run_app` returns once the application
+    /// exits or `WM_QUIT` is received, and [`EventLoopExtPumpEvents::pump_app_events`] can be
+    /// used to run it from another loop instead. There is no `CoreWindow`: the window must be
+    /// created with [`WindowAttributesWinRt::with_app_window`], and the features which need a
+    /// `CoreWindow` or an `ApplicationView`, like [`WindowExtWinRt::core_window`], are not
+    /// available.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`EventLoopExtPumpEvents::pump_app_events`]: winit_core::event_loop::pump_events::EventLoopExtPumpEvents::pump_app_events
+    fn with_dispatcher_queue(&mut self, dispatcher_queue: bool) -> &mut Self;
+}
+
+/// Additional methods on `EventLoop` that are specific to WinRT/UWP.
+pub trait EventLoopViewExtWinRt: Sized {
+    /// Runs `main` on the view thread.
+    ///
+    /// `CoreApplication::Run` always owns the process, and the `CoreWindow` events can only be
+    /// processed on the thread of the view. This starts the view and calls `main` from
+    /// `IFrameworkView::Run`, where it creates the application handler and passes it to
+    /// `EventLoop::run_app`, or drives winit from an external loop with
+    /// [`EventLoopExtPumpEvents::pump_app_events`]. The process exits once `main` returns.
+    ///
+    /// Without a view, or when attached to the view of the host, `main` is called on the calling
+    /// thread.
+    ///
+    /// [`EventLoopExtPumpEvents::pump_app_events`]: winit_core::event_loop::pump_events::EventLoopExtPumpEvents::pump_app_events
+    fn run_view_never_return<F: FnOnce(Self) + Send + 'static>(self, main: F) -> !;
+}
+
+/// Additional methods on [`CustomCursor`] that are specific to WinRT/UWP.
+pub trait CustomCursorExtWinRt {
+    /// Creates a cursor from a cursor resource in the application package.
+    ///
+    /// The resource is loaded through `CoreCursor` with `CoreCursorType::Custom`, so
+    /// `resource_id` must refer to a cursor compiled into the application's resource file.
+    fn from_resource_id(resource_id: u32) -> Self;
+}
+
+impl CustomCursorExtWinRt for CustomCursor {
+    fn from_resource_id(resource_id: u32) -> Self {
+        CustomCursor(Arc::new(WinRtCustomCursor { resource_id }))
+    }
 }
 
 /// Additional methods on [`Window`] that are specific to WinRT/UWP.
 pub trait WindowExtWinRt {
     /// Returns the underlying `CoreWindow`.
-    fn core_window(&self) -> WinRtCoreWindow;
+    ///
+    /// Returns `None` if the event loop runs without a view, see
+    /// [`EventLoopBuilderExtWinRt::with_headless`].
+    fn core_window(&self) -> Option<WinRtCoreWindow>;
+
+    /// Returns the `SwapChainPanel` hosting the window, see
+    /// [`WindowAttributesWinRt::with_swap_chain_panel`].
+    fn swap_chain_panel(&self) -> Option<IInspectable>;
+
+    /// Returns the raw `ApplicationView::VisibleBounds` rectangle.
+    ///
+    /// Unlike [`Window::safe_area`], this is not converted to insets, which some layout systems
+    /// need verbatim, e.g. to align with the system chrome on Xbox.
+    ///
+    /// Returns `None` if the view is not available.
+    ///
+    /// [`Window::safe_area`]: winit_core::window::Window::safe_area
+    fn visible_bounds(&self) -> Option<VisibleBounds>;
+
+    /// Requests the input pane (the touch keyboard) to be shown or hidden.
+    ///
+    /// Returns `true` if the system accepted the request. Changes to the area occluded by the
+    /// input pane are reported through
+    /// [`ApplicationHandlerExtWinRt::input_pane_occlusion_changed`].
+    fn set_input_pane_visible(&self, visible: bool) -> bool;
+
+    /// Sets whether the window extends beyond the visible bounds to cover the whole screen.
+    ///
+    /// On Xbox, the system keeps the window inside the TV-safe area by default, so that nothing
+    /// is cut off by the overscan of the TV. When enabled, the window covers the whole screen and
+    /// [`Window::safe_area`] reports the TV-safe area instead, so that backgrounds can be drawn to
+    /// the edges while the user interface stays inside the safe area.
+    ///
+    /// This sets `ApplicationView::SetDesiredBoundsMode`. Returns `true` if the system accepted
+    /// the request. The surface size changes with the bounds mode, which is reported as
+    /// [`WindowEvent::SurfaceResized`].
+    ///
+    /// [`Window::safe_area`]: winit_core::window::Window::safe_area
+    /// [`WindowEvent::SurfaceResized`]: winit_core::event::WindowEvent::SurfaceResized
+    fn set_extend_beyond_visible_bounds(&self, extend: bool) -> bool;
+
+    /// Returns whether the window extends beyond the visible bounds, see
+    /// [`WindowExtWinRt::set_extend_beyond_visible_bounds`].
+    fn extends_beyond_visible_bounds(&self) -> bool;
+
+    /// Sets whether keys the shell normally handles, e.g. Alt+Tab and the Windows key, are
+    /// delivered to the window instead while it is in the foreground.
+    ///
+    /// This is meant for kiosk applications and requires the restricted
+    /// `inputForegroundObservation` capability. The intercepted keys are reported as regular
+    /// [`WindowEvent::KeyboardInput`] events. Returns `true` if the system accepted the request.
+    ///
+    /// [`WindowEvent::KeyboardInput`]: winit_core::event::WindowEvent::KeyboardInput
+    fn set_key_interception(&self, intercept: bool) -> bool;
+
+    /// Starts dragging `data` out of the window, e.g. into another application.
+    ///
+    /// This must be called while a pointer button is pressed, usually in response to
+    /// [`WindowEvent::PointerMoved`]. The drag is driven by the system until the pointer is
+    /// released. Returns `true` if the drag was started.
+    ///
+    /// [`WindowEvent::PointerMoved`]: winit_core::event::WindowEvent::PointerMoved
+    fn start_drag(&self, data: DragData) -> bool;
+
+    /// Activates the `CoreWindow`, which dismisses the splash screen.
+    ///
+    /// This is only needed when the activation was deferred with
+    /// [`EventLoopBuilderExtWinRt::with_deferred_activation`].
+    fn activate(&self);
+
+    /// Closes the view of the window.
+    ///
+    /// The view is consolidated with `ApplicationView::TryConsolidateAsync`, or closed with
+    /// `CoreWindow::Close` if the system refuses. [`WindowEvent::Destroyed`] is delivered once the
+    /// view is closed; the `Window` should still be dropped afterwards. Closing the last view
+    /// usually ends the application.
+    ///
+    /// [`WindowEvent::Destroyed`]: winit_core::event::WindowEvent::Destroyed
+    fn close(&self);
+
+    /// Returns the area of the window currently occluded by the input pane, if it is shown.
+    fn input_pane_occluded_rect(&self) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)>;
+
+    /// Returns the insets of the system caption buttons and the height of the title bar.
+    ///
+    /// This is needed to draw custom chrome when the view extends into the title bar, see
+    /// [`Window::set_decorations`]. Changes are reported through
+    /// [`ApplicationHandlerExtWinRt::title_bar_layout_changed`].
+    ///
+    /// [`Window::set_decorations`]: winit_core::window::Window::set_decorations
+    fn title_bar_metrics(&self) -> Option<TitleBarMetrics>;
+
+    /// Returns statistics about the frames recently presented on the primary display.
+    ///
+    /// This can be used to measure latency and adjust frame pacing. The statistics are taken from
+    /// `IDXGIOutput::GetFrameStatistics`, and are `None` when the system doesn't provide them,
+    /// e.g. before the first frame was presented.
+    fn frame_statistics(&self) -> Option<FrameStatistics>;
+
+    /// Returns the rotation between the native orientation of the display and its current
+    /// orientation.
+    ///
+    /// DirectX applications can pass this to `IDXGISwapChain1::SetRotation` and pre-rotate their
+    /// frames, which avoids a copy by the compositor. Changes are reported through
+    /// [`ApplicationHandlerExtWinRt::surface_rotation_changed`].
+    ///
+    /// Returns `None` if the display information isn't available.
+    fn surface_rotation(&self) -> Option<SurfaceRotation>;
+
+    /// Sets whether the display should stay on while the window exists, e.g. during video
+    /// playback.
+    ///
+    /// This wraps `DisplayRequest::{RequestActive, RequestRelease}`. Repeated calls with the same
+    /// value have no effect, and the request is released when the window is dropped. The system
+    /// also releases it while the application is suspended.
+    fn set_display_keep_awake(&self, keep_awake: bool);
+
+    /// Returns the `CompositionTarget` of the window, to layer `Windows.UI.Composition` content
+    /// with the swapchain.
+    ///
+    /// The target is created for the view on the first call, with a new `Compositor` that is
+    /// available through `CompositionTarget::Compositor`. Visuals assigned with
+    /// `CompositionTarget::SetRoot` are drawn above a swapchain created for the `CoreWindow`; a
+    /// swapchain can also be placed in the visual tree with `ICompositorInterop`. Returns `None`
+    /// if the target can't be created, e.g. in headless mode.
+    fn composition_target(&self) -> Option<CompositionTarget>;
+
+    /// Returns the `ApplicationView` of the window, to reach APIs winit doesn't wrap.
+    ///
+    /// Settings changed through the view, e.g. the title or the bounds mode, aren't tracked by
+    /// winit. Most of its methods have to be called on the view thread. Returns `None` if the
+    /// view is not available, e.g. in headless mode.
+    fn application_view(&self) -> Option<ApplicationView>;
+
+    /// Returns the `HWND` backing the `CoreWindow`, to interoperate with Win32 libraries.
+    ///
+    /// This uses `ICoreWindowInterop`, which is only available to desktop bridge applications and
+    /// on desktop devices; `None` is returned otherwise. The window is still owned by the
+    /// `CoreWindow`, so it must not be destroyed or subclassed.
+    ///
+    /// For a window created with [`WindowAttributesWinRt::with_app_window`], this is the `HWND`
+    /// of the `AppWindow`.
+    fn hwnd(&self) -> Option<NonZeroIsize>;
+
+    /// Returns the `HolographicSpace` of the window, see
+    /// [`EventLoopBuilderExtWinRt::with_holographic`].
+    ///
+    /// Returns `None` if holographic mode isn't enabled or the space couldn't be created, e.g.
+    /// because no headset is available.
+    fn holographic_space(&self) -> Option<HolographicSpace>;
+
+    /// Returns the holographic camera with the given ID, as reported by
+    /// [`ApplicationHandlerExtWinRt::holographic_camera_added`].
+    fn holographic_camera(&self, camera_id: u32) -> Option<HolographicCamera>;
 
     /// Compatibility shim for Win32 DWM API. No-op on WinRT/UWP.
     fn set_undecorated_shadow(&self, shadow: bool);
@@ -94,10 +879,14 @@ pub trait WindowExtWinRt {
     /// Compatibility shim for Win32 DWM API. No-op on WinRT/UWP.
     fn set_border_color(&self, color: Option<Color>);
 
-    /// Compatibility shim for Win32 DWM API. No-op on WinRT/UWP.
+    /// Sets the background color of the title bar and its caption buttons.
+    ///
+    /// `None` and [`Color::SYSTEM_DEFAULT`] restore the system color.
     fn set_title_background_color(&self, color: Option<Color>);
 
-    /// Compatibility shim for Win32 DWM API. No-op on WinRT/UWP.
+    /// Sets the color of the window title and the caption button glyphs.
+    ///
+    /// [`Color::SYSTEM_DEFAULT`] restores the system color.
     fn set_title_text_color(&self, color: Color);
 
     /// Compatibility shim for Win32 DWM API. No-op on WinRT/UWP.
@@ -109,14 +898,176 @@ impl EventLoopExtWinRt for dyn CoreActiveEventLoop + '_ {
         let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
         event_loop.dispatcher()
     }
+
+    fn run_on_dispatcher<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<(), RequestError> {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.run_on_dispatcher(Box::new(f))
+    }
+
+    fn run_on_dispatcher_with_result<T, F>(&self, f: F) -> Result<mpsc::Receiver<T>, RequestError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.run_on_dispatcher(move || {
+            let _ = sender.send(f());
+        })?;
+        Ok(receiver)
+    }
+
+    fn mouse_capabilities(&self) -> MouseCapabilities {
+        capabilities::mouse_capabilities()
+    }
+
+    fn touch_capabilities(&self) -> TouchCapabilities {
+        capabilities::touch_capabilities()
+    }
+
+    fn is_layout_right_to_left(&self) -> bool {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.is_layout_right_to_left()
+    }
+
+    fn event_timestamp(&self) -> Option<Duration> {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.event_timestamp()
+    }
+
+    fn animations_enabled(&self) -> bool {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.animations_enabled()
+    }
+
+    fn exit_with_code(&self, code: i32) {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.exit_with_code(code);
+    }
+
+    fn set_auto_rotation_preferences(&self, orientations: Orientations) {
+        orientation::set_auto_rotation_preferences(orientations)
+    }
+
+    fn launch_arguments(&self) -> Option<LaunchArguments> {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.launch_arguments()
+    }
+
+    fn clipboard_text(&self) -> Option<String> {
+        clipboard::clipboard_text()
+    }
+
+    fn set_clipboard_text(&self, text: &str) -> bool {
+        clipboard::set_clipboard_text(text)
+    }
+
+    fn register_cursor_resource(&self, source: CustomCursorSource, resource_id: u32) {
+        let event_loop = self.cast_ref::<ActiveEventLoop>().unwrap();
+        event_loop.runner.register_cursor_resource(source, resource_id)
+    }
 }
 
 impl WindowExtWinRt for dyn CoreWindow + '_ {
-    fn core_window(&self) -> WinRtCoreWindow {
+    fn core_window(&self) -> Option<WinRtCoreWindow> {
         let window = self.cast_ref::<Window>().unwrap();
         window.core_window()
     }
 
+    fn swap_chain_panel(&self) -> Option<IInspectable> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.swap_chain_panel()
+    }
+
+    fn visible_bounds(&self) -> Option<VisibleBounds> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.visible_bounds()
+    }
+
+    fn set_input_pane_visible(&self, visible: bool) -> bool {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.set_input_pane_visible(visible)
+    }
+
+    fn set_extend_beyond_visible_bounds(&self, extend: bool) -> bool {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.set_extend_beyond_visible_bounds(extend)
+    }
+
+    fn extends_beyond_visible_bounds(&self) -> bool {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.extends_beyond_visible_bounds()
+    }
+
+    fn set_key_interception(&self, intercept: bool) -> bool {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.set_key_interception(intercept)
+    }
+
+    fn start_drag(&self, data: DragData) -> bool {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.start_drag(data)
+    }
+
+    fn activate(&self) {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.activate()
+    }
+
+    fn close(&self) {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.close()
+    }
+
+    fn input_pane_occluded_rect(&self) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.input_pane_occluded_rect()
+    }
+
+    fn title_bar_metrics(&self) -> Option<TitleBarMetrics> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.title_bar_metrics()
+    }
+
+    fn frame_statistics(&self) -> Option<FrameStatistics> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.frame_statistics()
+    }
+
+    fn surface_rotation(&self) -> Option<SurfaceRotation> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.surface_rotation()
+    }
+
+    fn set_display_keep_awake(&self, keep_awake: bool) {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.set_display_keep_awake(keep_awake)
+    }
+
+    fn composition_target(&self) -> Option<CompositionTarget> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.composition_target()
+    }
+
+    fn application_view(&self) -> Option<ApplicationView> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.application_view()
+    }
+
+    fn hwnd(&self) -> Option<NonZeroIsize> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.hwnd()
+    }
+
+    fn holographic_space(&self) -> Option<HolographicSpace> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.holographic_space()
+    }
+
+    fn holographic_camera(&self, camera_id: u32) -> Option<HolographicCamera> {
+        let window = self.cast_ref::<Window>().unwrap();
+        window.holographic_camera(camera_id)
+    }
+
     fn set_undecorated_shadow(&self, shadow: bool) {
         let _ = shadow;
     }
@@ -130,11 +1081,13 @@ impl WindowExtWinRt for dyn CoreWindow + '_ {
     }
 
     fn set_title_background_color(&self, color: Option<Color>) {
-        let _ = color;
+        let window = self.cast_ref::<Window>().unwrap();
+        window.set_title_background_color(color.unwrap_or(Color::SYSTEM_DEFAULT))
     }
 
     fn set_title_text_color(&self, color: Color) {
-        let _ = color;
+        let window = self.cast_ref::<Window>().unwrap();
+        window.set_title_text_color(color)
     }
 
     fn set_corner_preference(&self, preference: CornerPreference) {
@@ -142,6 +1095,24 @@ impl WindowExtWinRt for dyn CoreWindow + '_ {
     }
 }
 
+/// Additional methods on [`MonitorHandle`] that are specific to WinRT/UWP.
+///
+/// [`MonitorHandle`]: CoreMonitorHandle
+pub trait MonitorHandleExtWinRt {
+    /// Returns the advanced color capabilities of the display, e.g. to decide whether to create
+    /// an HDR swapchain.
+    ///
+    /// Returns `None` if the display information isn't available.
+    fn advanced_color_info(&self) -> Option<AdvancedColorInfo>;
+}
+
+impl MonitorHandleExtWinRt for CoreMonitorHandle {
+    fn advanced_color_info(&self) -> Option<AdvancedColorInfo> {
+        let monitor = self.cast_ref::<MonitorHandle>().unwrap();
+        monitor.advanced_color_info()
+    }
+}
+
 pub fn physicalkey_to_scancode(physical_key: PhysicalKey) -> Option<u32> {
     match physical_key {
         PhysicalKey::Unidentified(NativeKeyCode::Windows(scancode)) => Some(scancode as u32),
@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::num::{NonZeroU16, NonZeroU32};
 use std::sync::Arc;
 
@@ -6,10 +7,14 @@ use dpi::{PhysicalPosition, PhysicalSize};
 use winit_core::monitor::{MonitorHandle as RootMonitorHandle, MonitorHandleProvider, VideoMode};
 
 use windows::core::AgileReference;
-use windows::Graphics::Display::DisplayInformation;
-use windows::Graphics::Display::Core::HdmiDisplayInformation;
+use windows::Devices::Display::DisplayMonitor;
+use windows::Devices::Enumeration::DeviceInformation;
+use windows::Foundation::Collections::IVectorView;
+use windows::Graphics::Display::Core::{HdmiDisplayInformation, HdmiDisplayMode};
+use windows::Graphics::Display::{AdvancedColorKind as WinRtAdvancedColorKind, DisplayInformation};
 
 use crate::util::ensure_winrt_initialized;
+use crate::{AdvancedColorInfo, AdvancedColorKind};
 
 #[derive(Debug, Clone)]
 pub struct MonitorHandle {
@@ -25,6 +30,28 @@ impl MonitorHandle {
         Self { scale_factor, display_info }
     }
 
+    pub(crate) fn advanced_color_info(&self) -> Option<AdvancedColorInfo> {
+        ensure_winrt_initialized();
+        let info = self.display_info.as_ref()?.resolve().ok()?.GetAdvancedColorInfo().ok()?;
+        let kind = match info.CurrentAdvancedColorKind().ok()? {
+            WinRtAdvancedColorKind::HighDynamicRange => AdvancedColorKind::HighDynamicRange,
+            WinRtAdvancedColorKind::WideColorGamut => AdvancedColorKind::WideColorGamut,
+            _ => AdvancedColorKind::StandardDynamicRange,
+        };
+        Some(AdvancedColorInfo {
+            kind,
+            hdr_supported: info
+                .IsAdvancedColorKindAvailable(WinRtAdvancedColorKind::HighDynamicRange)
+                .unwrap_or(false),
+            max_luminance: info.MaxLuminanceInNits().unwrap_or(0.0),
+            min_luminance: info.MinLuminanceInNits().unwrap_or(0.0),
+            max_average_full_frame_luminance: info
+                .MaxAverageFullFrameLuminanceInNits()
+                .unwrap_or(0.0),
+            sdr_white_level: info.SdrWhiteLevelInNits().unwrap_or(0.0),
+        })
+    }
+
     pub(crate) fn to_core(self) -> RootMonitorHandle {
         RootMonitorHandle(Arc::new(self))
     }
@@ -40,7 +67,7 @@ impl MonitorHandleProvider for MonitorHandle {
     }
 
     fn name(&self) -> Option<Cow<'_, str>> {
-        None
+        display_monitor_name().map(Cow::Owned)
     }
 
     fn position(&self) -> Option<PhysicalPosition<i32>> {
@@ -60,35 +87,100 @@ impl MonitorHandleProvider for MonitorHandle {
         let (bit_depth, refresh_rate_millihertz) = HdmiDisplayInformation::GetForCurrentView()
             .ok()
             .and_then(|hdi| hdi.GetCurrentDisplayMode().ok())
-            .map(|mode| {
-                let bit_depth = mode
-                    .BitsPerPixel()
-                    .ok()
-                    .and_then(|bpp| u16::try_from(bpp).ok())
-                    .and_then(NonZeroU16::new);
-
-                let refresh_rate_millihertz = mode.RefreshRate().ok().and_then(|hz| {
-                    let hz = hz as f64;
-                    let mhz = (hz * 1000.0).round();
-                    if mhz.is_finite() && mhz > 0.0 && mhz <= u32::MAX as f64 {
-                        NonZeroU32::new(mhz as u32)
-                    } else {
-                        None
-                    }
-                });
-
-                (bit_depth, refresh_rate_millihertz)
-            })
+            .map(|mode| (hdmi_bit_depth(&mode), hdmi_refresh_rate_millihertz(&mode)))
             .unwrap_or((None, None));
 
-        Some(VideoMode::new(
-            PhysicalSize::new(width, height),
-            bit_depth,
-            refresh_rate_millihertz,
-        ))
+        Some(VideoMode::new(PhysicalSize::new(width, height), bit_depth, refresh_rate_millihertz))
     }
 
     fn video_modes(&self) -> Box<dyn Iterator<Item = VideoMode>> {
-        Box::new(self.current_video_mode().into_iter())
+        // Display modes can only be enumerated through HDMI, i.e. on Xbox. The same resolution is
+        // listed once per color space, pixel encoding and stereo setting, which `VideoMode`
+        // doesn't distinguish.
+        let mut seen = HashSet::new();
+        let modes: Vec<_> = hdmi_display_modes()
+            .map(|(_, modes)| {
+                modes
+                    .into_iter()
+                    .filter(|mode| !mode.StereoEnabled().unwrap_or(false))
+                    .filter_map(|mode| hdmi_video_mode(&mode))
+                    .filter(|mode| seen.insert(*mode))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if modes.is_empty() {
+            Box::new(self.current_video_mode().into_iter())
+        } else {
+            Box::new(modes.into_iter())
+        }
     }
 }
+
+/// Returns the friendly name of the display.
+///
+/// UWP doesn't expose which monitor a view is on, so this uses the first connected monitor, which
+/// is the only one on Xbox and the built-in panel on most other devices.
+fn display_monitor_name() -> Option<String> {
+    ensure_winrt_initialized();
+    let selector = DisplayMonitor::GetDeviceSelector().ok()?;
+    let device =
+        DeviceInformation::FindAllAsyncAqsFilter(&selector).ok()?.get().ok()?.GetAt(0).ok()?;
+    let id = device.Id().ok()?;
+    let name = DisplayMonitor::FromInterfaceIdAsync(&id)
+        .and_then(|monitor| monitor.get())
+        .and_then(|monitor| monitor.DisplayName())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .or_else(|| device.Name().ok())?;
+    Some(name.to_string_lossy())
+}
+
+fn hdmi_display_modes() -> Option<(HdmiDisplayInformation, IVectorView<HdmiDisplayMode>)> {
+    ensure_winrt_initialized();
+    let hdi = HdmiDisplayInformation::GetForCurrentView().ok()?;
+    let modes = hdi.GetSupportedDisplayModes().ok()?;
+    Some((hdi, modes))
+}
+
+/// Finds the HDMI display mode matching `video_mode`, if the display supports mode switching.
+pub(crate) fn find_hdmi_display_mode(
+    video_mode: &VideoMode,
+) -> Option<(HdmiDisplayInformation, HdmiDisplayMode)> {
+    let (hdi, modes) = hdmi_display_modes()?;
+    let mode = modes.into_iter().find(|mode| {
+        !mode.StereoEnabled().unwrap_or(false) && hdmi_video_mode(mode).as_ref() == Some(video_mode)
+    })?;
+    Some((hdi, mode))
+}
+
+/// Returns the video mode the HDMI display is currently set to.
+pub(crate) fn current_hdmi_video_mode() -> Option<VideoMode> {
+    ensure_winrt_initialized();
+    let mode = HdmiDisplayInformation::GetForCurrentView().ok()?.GetCurrentDisplayMode().ok()?;
+    hdmi_video_mode(&mode)
+}
+
+fn hdmi_video_mode(mode: &HdmiDisplayMode) -> Option<VideoMode> {
+    let width = mode.ResolutionWidthInRawPixels().ok()?;
+    let height = mode.ResolutionHeightInRawPixels().ok()?;
+    Some(VideoMode::new(
+        PhysicalSize::new(width, height),
+        hdmi_bit_depth(mode),
+        hdmi_refresh_rate_millihertz(mode),
+    ))
+}
+
+fn hdmi_bit_depth(mode: &HdmiDisplayMode) -> Option<NonZeroU16> {
+    mode.BitsPerPixel().ok().and_then(NonZeroU16::new)
+}
+
+fn hdmi_refresh_rate_millihertz(mode: &HdmiDisplayMode) -> Option<NonZeroU32> {
+    mode.RefreshRate().ok().and_then(|hz| {
+        let mhz = (hz * 1000.0).round();
+        if mhz.is_finite() && mhz > 0.0 && mhz <= u32::MAX as f64 {
+            NonZeroU32::new(mhz as u32)
+        } else {
+            None
+        }
+    })
+}
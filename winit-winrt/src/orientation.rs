@@ -0,0 +1,35 @@
+use windows::Graphics::Display::{DisplayInformation, DisplayOrientations};
+
+use crate::util::ensure_winrt_initialized;
+use crate::{Orientations, SurfaceRotation};
+
+/// Restricts the orientations the system rotates the application to.
+pub(crate) fn set_auto_rotation_preferences(orientations: Orientations) {
+    ensure_winrt_initialized();
+    // `Orientations` uses the same bits as `DisplayOrientations`.
+    let orientations = DisplayOrientations(orientations.bits() as u32);
+    let _ = DisplayInformation::SetAutoRotationPreferences(orientations);
+}
+
+/// Returns the rotation between the native orientation of the display and its current
+/// orientation.
+pub(crate) fn surface_rotation(info: &DisplayInformation) -> Option<SurfaceRotation> {
+    let native = info.NativeOrientation().ok()?;
+    let current = info.CurrentOrientation().ok()?;
+    let rotation = match (native, current) {
+        (DisplayOrientations::Landscape, DisplayOrientations::Portrait)
+        | (DisplayOrientations::Portrait, DisplayOrientations::LandscapeFlipped) => {
+            SurfaceRotation::Rotate270
+        },
+        (DisplayOrientations::Landscape, DisplayOrientations::LandscapeFlipped)
+        | (DisplayOrientations::Portrait, DisplayOrientations::PortraitFlipped) => {
+            SurfaceRotation::Rotate180
+        },
+        (DisplayOrientations::Landscape, DisplayOrientations::PortraitFlipped)
+        | (DisplayOrientations::Portrait, DisplayOrientations::Landscape) => {
+            SurfaceRotation::Rotate90
+        },
+        _ => SurfaceRotation::Identity,
+    };
+    Some(rotation)
+}
@@ -0,0 +1,314 @@
+//! Hosting the window in a XAML `SwapChainPanel`, see
+//! `WindowAttributesWinRt::with_swap_chain_panel`.
+//!
+//! The `windows` crate doesn't project `Windows.UI.Xaml`, so the two interfaces of the panel winit
+//! needs, and the delegates of their events, are declared here. Slots winit doesn't call are
+//! declared without arguments, only to keep the layout of the vtables.
+
+use std::ffi::c_void;
+use std::sync::{Arc, Mutex};
+
+use dpi::LogicalSize;
+use tracing::warn;
+use windows::core::imp::ConstBuffer;
+use windows::core::{
+    implement, interface, AgileReference, IInspectable, IUnknown, IUnknown_Vtbl, Interface,
+    Result as WinResult, RuntimeName, RuntimeType, GUID, HRESULT,
+};
+use windows::Foundation::{EventRegistrationToken, TypedEventHandler};
+use windows::UI::Core::{
+    CoreDispatcher, CoreIndependentInputSource, CoreInputDeviceTypes, CoreProcessEventsOption,
+};
+use winit_core::window::WindowId;
+
+use crate::event_loop::Runner;
+use crate::independent_input::register_pointer_handlers;
+use crate::util::ensure_winrt_initialized;
+
+/// `Windows.UI.Xaml.Controls.ISwapChainPanel`.
+#[interface("c589644f-eba8-427a-b75a-9f1f93a11ae9")]
+unsafe trait ISwapChainPanel: IUnknown {
+    fn GetIids(&self) -> HRESULT;
+    fn GetRuntimeClassName(&self) -> HRESULT;
+    fn GetTrustLevel(&self) -> HRESULT;
+    fn CompositionScaleX(&self, value: *mut f32) -> HRESULT;
+    fn CompositionScaleY(&self, value: *mut f32) -> HRESULT;
+    fn add_CompositionScaleChanged(
+        &self,
+        handler: *mut c_void,
+        token: *mut EventRegistrationToken,
+    ) -> HRESULT;
+    fn remove_CompositionScaleChanged(&self, token: EventRegistrationToken) -> HRESULT;
+    fn CreateCoreIndependentInputSource(
+        &self,
+        device_types: CoreInputDeviceTypes,
+        source: *mut *mut c_void,
+    ) -> HRESULT;
+}
+
+/// `Windows.UI.Xaml.IFrameworkElement`, up to `SizeChanged`.
+#[interface("a391d09b-4a99-4b7c-9d8d-6fa5d01f6fbf")]
+unsafe trait IFrameworkElement: IUnknown {
+    fn GetIids(&self) -> HRESULT;
+    fn GetRuntimeClassName(&self) -> HRESULT;
+    fn GetTrustLevel(&self) -> HRESULT;
+    fn Triggers(&self) -> HRESULT;
+    fn Resources(&self) -> HRESULT;
+    fn SetResources(&self) -> HRESULT;
+    fn Tag(&self) -> HRESULT;
+    fn SetTag(&self) -> HRESULT;
+    fn Language(&self) -> HRESULT;
+    fn SetLanguage(&self) -> HRESULT;
+    fn ActualWidth(&self, value: *mut f64) -> HRESULT;
+    fn ActualHeight(&self, value: *mut f64) -> HRESULT;
+    fn Width(&self) -> HRESULT;
+    fn SetWidth(&self) -> HRESULT;
+    fn Height(&self) -> HRESULT;
+    fn SetHeight(&self) -> HRESULT;
+    fn MinWidth(&self) -> HRESULT;
+    fn SetMinWidth(&self) -> HRESULT;
+    fn MaxWidth(&self) -> HRESULT;
+    fn SetMaxWidth(&self) -> HRESULT;
+    fn MinHeight(&self) -> HRESULT;
+    fn SetMinHeight(&self) -> HRESULT;
+    fn MaxHeight(&self) -> HRESULT;
+    fn SetMaxHeight(&self) -> HRESULT;
+    fn HorizontalAlignment(&self) -> HRESULT;
+    fn SetHorizontalAlignment(&self) -> HRESULT;
+    fn VerticalAlignment(&self) -> HRESULT;
+    fn SetVerticalAlignment(&self) -> HRESULT;
+    fn Margin(&self) -> HRESULT;
+    fn SetMargin(&self) -> HRESULT;
+    fn Name(&self) -> HRESULT;
+    fn SetName(&self) -> HRESULT;
+    fn BaseUri(&self) -> HRESULT;
+    fn DataContext(&self) -> HRESULT;
+    fn SetDataContext(&self) -> HRESULT;
+    fn Style(&self) -> HRESULT;
+    fn SetStyle(&self) -> HRESULT;
+    fn Parent(&self) -> HRESULT;
+    fn FlowDirection(&self) -> HRESULT;
+    fn SetFlowDirection(&self) -> HRESULT;
+    fn add_Loaded(&self) -> HRESULT;
+    fn remove_Loaded(&self) -> HRESULT;
+    fn add_Unloaded(&self) -> HRESULT;
+    fn remove_Unloaded(&self) -> HRESULT;
+    fn add_SizeChanged(&self, handler: *mut c_void, token: *mut EventRegistrationToken) -> HRESULT;
+    fn remove_SizeChanged(&self, token: EventRegistrationToken) -> HRESULT;
+}
+
+/// `Windows.UI.Xaml.Controls.SwapChainPanel`, the sender of `CompositionScaleChanged`.
+///
+/// Its event handler is a `TypedEventHandler<SwapChainPanel, Object>`, whose IID is derived from
+/// the name and the default interface of the class.
+#[repr(transparent)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SwapChainPanel(IUnknown);
+
+unsafe impl Interface for SwapChainPanel {
+    type Vtable = ISwapChainPanel_Vtbl;
+    const IID: GUID = <ISwapChainPanel as Interface>::IID;
+}
+
+impl RuntimeName for SwapChainPanel {
+    const NAME: &'static str = "Windows.UI.Xaml.Controls.SwapChainPanel";
+}
+
+impl RuntimeType for SwapChainPanel {
+    const SIGNATURE: ConstBuffer = ConstBuffer::for_class::<Self>();
+}
+
+/// `Windows.UI.Xaml.SizeChangedEventHandler`, the handler of `SizeChanged`.
+#[interface("1115b13b-8c0d-4b4c-a9d4-ebec8c0bdb6a")]
+unsafe trait SizeChangedEventHandler: IUnknown {
+    fn Invoke(&self, sender: *mut c_void, args: *mut c_void) -> HRESULT;
+}
+
+/// Refreshes the window when the panel sending `SizeChanged` was resized.
+#[implement(SizeChangedEventHandler)]
+struct SizeChangedHandler {
+    runner: Arc<Runner>,
+    window_id: WindowId,
+}
+
+impl SizeChangedEventHandler_Impl for SizeChangedHandler {
+    unsafe fn Invoke(&self, sender: *mut c_void, _args: *mut c_void) -> HRESULT {
+        let panel = unsafe { IInspectable::from_raw_borrowed(&sender) };
+        self.runner
+            .catch_panic(|| match panel {
+                Some(panel) => refresh(&self.runner, self.window_id, panel),
+                None => Ok(()),
+            })
+            .into()
+    }
+}
+
+/// The `SwapChainPanel` hosting a window, with the handlers winit registered on it.
+pub(crate) struct PanelHost {
+    panel: AgileReference<IInspectable>,
+    /// The address of the panel for `raw-window-handle`, kept alive by `panel`.
+    raw: usize,
+    scale_token: EventRegistrationToken,
+    size_token: EventRegistrationToken,
+    input: Arc<Mutex<InputThread>>,
+}
+
+/// The state of the thread processing the input of the panel.
+#[derive(Default)]
+struct InputThread {
+    /// The dispatcher of the input source, set once it was created.
+    dispatcher: Option<AgileReference<CoreDispatcher>>,
+    /// Whether the panel was detached, possibly before the input source was created.
+    stopped: bool,
+}
+
+impl PanelHost {
+    /// Makes the panel report the surface size, the scale factor and the pointer input of the
+    /// window.
+    ///
+    /// This must be called on the thread of the panel, i.e. the view thread of the XAML
+    /// application.
+    pub(crate) fn attach(
+        runner: &Arc<Runner>,
+        window_id: WindowId,
+        panel: &AgileReference<IInspectable>,
+    ) -> WinResult<Self> {
+        let inspectable = panel.resolve()?;
+        let swap_chain_panel = inspectable.cast::<ISwapChainPanel>()?;
+        let element = inspectable.cast::<IFrameworkElement>()?;
+
+        refresh(runner, window_id, &inspectable)?;
+
+        // Both events are raised with the panel as the sender.
+        let scale_handler = TypedEventHandler::<SwapChainPanel, IInspectable>::new({
+            let runner = Arc::clone(runner);
+            move |sender, _| {
+                runner.catch_panic(|| match sender {
+                    Some(panel) => refresh(&runner, window_id, &panel.cast()?),
+                    None => Ok(()),
+                })
+            }
+        });
+        let size_handler: SizeChangedEventHandler =
+            SizeChangedHandler { runner: Arc::clone(runner), window_id }.into();
+        let mut scale_token = EventRegistrationToken::default();
+        let mut size_token = EventRegistrationToken::default();
+        unsafe {
+            swap_chain_panel
+                .add_CompositionScaleChanged(scale_handler.as_raw(), &mut scale_token)
+                .ok()?;
+            element.add_SizeChanged(size_handler.as_raw(), &mut size_token).ok()?;
+        }
+
+        runner.set_panel_hosted(true);
+        let input = Arc::new(Mutex::new(InputThread::default()));
+        start_input_thread(runner, window_id, panel.clone(), Arc::clone(&input));
+
+        Ok(Self {
+            panel: panel.clone(),
+            raw: inspectable.as_raw() as usize,
+            scale_token,
+            size_token,
+            input,
+        })
+    }
+
+    pub(crate) fn panel(&self) -> Option<IInspectable> {
+        ensure_winrt_initialized();
+        self.panel.resolve().ok()
+    }
+
+    pub(crate) fn raw(&self) -> *mut c_void {
+        self.raw as *mut c_void
+    }
+
+    /// Removes the handlers from the panel and stops its input thread.
+    ///
+    /// This must be called on the thread of the panel.
+    pub(crate) fn detach(&self, runner: &Runner) {
+        {
+            let mut input = self.input.lock().unwrap();
+            input.stopped = true;
+            if let Some(dispatcher) = input.dispatcher.take().and_then(|agile| agile.resolve().ok())
+            {
+                let _ = dispatcher.StopProcessEvents();
+            }
+        }
+        if let Ok(panel) = self.panel.resolve() {
+            unsafe {
+                if let Ok(swap_chain_panel) = panel.cast::<ISwapChainPanel>() {
+                    let _ = swap_chain_panel.remove_CompositionScaleChanged(self.scale_token);
+                }
+                if let Ok(element) = panel.cast::<IFrameworkElement>() {
+                    let _ = element.remove_SizeChanged(self.size_token);
+                }
+            }
+        }
+        runner.set_panel_hosted(false);
+    }
+}
+
+/// Reads the size and the composition scale of the panel.
+///
+/// The composition scale includes the scale of the display as well as the zoom of enclosing
+/// `ScrollViewer`s and render transforms, so that the swapchain matches the pixels on screen.
+fn refresh(runner: &Runner, window_id: WindowId, panel: &IInspectable) -> WinResult<()> {
+    let swap_chain_panel = panel.cast::<ISwapChainPanel>()?;
+    let element = panel.cast::<IFrameworkElement>()?;
+    let mut scale = 1.0f32;
+    let mut width = 0.0f64;
+    let mut height = 0.0f64;
+    unsafe {
+        // Only uniform scales can be reported, `CompositionScaleY` is the same for those.
+        swap_chain_panel.CompositionScaleX(&mut scale).ok()?;
+        element.ActualWidth(&mut width).ok()?;
+        element.ActualHeight(&mut height).ok()?;
+    }
+    let scale_factor = if scale > 0.0 { scale as f64 } else { 1.0 };
+    runner.update_scale_factor(window_id, scale_factor);
+    let size = LogicalSize::new(width, height).to_physical::<u32>(scale_factor);
+    runner.update_surface_size(window_id, size);
+    Ok(())
+}
+
+/// Delivers the pointer input over the panel on a dedicated thread, like
+/// `independent_input::start_input_thread`. The panel then keeps the input from the `CoreWindow`.
+fn start_input_thread(
+    runner: &Arc<Runner>,
+    window_id: WindowId,
+    panel: AgileReference<IInspectable>,
+    state: Arc<Mutex<InputThread>>,
+) {
+    let runner = Arc::clone(runner);
+    let _ = std::thread::Builder::new().name("winit panel input".into()).spawn(move || {
+        ensure_winrt_initialized();
+        // The input source raises its events on the thread it was created on, while its
+        // dispatcher processes them.
+        let result = panel.resolve().and_then(|panel| {
+            let swap_chain_panel = panel.cast::<ISwapChainPanel>()?;
+            let mut source = std::ptr::null_mut();
+            let device_types = CoreInputDeviceTypes::Mouse
+                | CoreInputDeviceTypes::Touch
+                | CoreInputDeviceTypes::Pen;
+            let source = unsafe {
+                swap_chain_panel
+                    .CreateCoreIndependentInputSource(device_types, &mut source)
+                    .ok()?;
+                CoreIndependentInputSource::from_raw(source)
+            };
+            register_pointer_handlers(&source, &runner, window_id)?;
+            let dispatcher = source.Dispatcher()?;
+            {
+                let mut state = state.lock().unwrap();
+                if state.stopped {
+                    return Ok(());
+                }
+                state.dispatcher = AgileReference::new(&dispatcher).ok();
+            }
+            dispatcher.ProcessEvents(CoreProcessEventsOption::ProcessUntilQuit)
+        });
+        if let Err(err) = result {
+            warn!("panel input stopped: {err}");
+        }
+    });
+}
@@ -0,0 +1,19 @@
+use windows::UI::ViewManagement::{UIColorType, UISettings};
+use winit_core::window::Theme;
+
+use crate::util::ensure_winrt_initialized;
+
+/// Returns the system light/dark mode.
+pub(crate) fn system_theme() -> Option<Theme> {
+    ensure_winrt_initialized();
+    let settings = UISettings::new().ok()?;
+    theme_from_settings(&settings)
+}
+
+/// Derives the theme from the system background color, which is black in dark mode and white in
+/// light mode.
+pub(crate) fn theme_from_settings(settings: &UISettings) -> Option<Theme> {
+    let background = settings.GetColorValue(UIColorType::Background).ok()?;
+    let luminance = (2 * background.R as u32 + 5 * background.G as u32 + background.B as u32) / 8;
+    Some(if luminance < 128 { Theme::Dark } else { Theme::Light })
+}
@@ -0,0 +1,126 @@
+use dpi::LogicalUnit;
+use windows::core::{implement, Array, Result as WinResult, GUID, HRESULT, HSTRING};
+use windows::ApplicationModel::Core::{CoreApplication, CoreApplicationViewTitleBar};
+use windows::Foundation::{
+    DateTime, IPropertyValue, IPropertyValue_Impl, IReference, IReference_Impl, Point,
+    PropertyType, Rect, Size, TimeSpan,
+};
+use windows::UI::Color as UiColor;
+use windows::UI::ViewManagement::{ApplicationView, ApplicationViewTitleBar};
+
+use crate::util::ensure_winrt_initialized;
+use crate::{Color, TitleBarMetrics};
+
+const TYPE_E_TYPEMISMATCH: HRESULT = HRESULT(0x8002_8ca0_u32 as i32);
+
+/// Returns the title bar of the current view.
+pub(crate) fn title_bar() -> Option<ApplicationViewTitleBar> {
+    ensure_winrt_initialized();
+    ApplicationView::GetForCurrentView().ok()?.TitleBar().ok()
+}
+
+/// Returns the title bar of the current `CoreApplicationView`, which controls whether the view
+/// extends into the title bar.
+pub(crate) fn core_title_bar() -> Option<CoreApplicationViewTitleBar> {
+    ensure_winrt_initialized();
+    CoreApplication::GetCurrentView().ok()?.TitleBar().ok()
+}
+
+/// Returns the caption button insets and title bar height, converted to physical pixels.
+pub(crate) fn title_bar_metrics(scale_factor: f64) -> Option<TitleBarMetrics> {
+    let title_bar = core_title_bar()?;
+    let to_physical = |value: f64| LogicalUnit::new(value).to_physical::<u32>(scale_factor).0;
+    Some(TitleBarMetrics {
+        left_inset: to_physical(title_bar.SystemOverlayLeftInset().ok()?),
+        right_inset: to_physical(title_bar.SystemOverlayRightInset().ok()?),
+        height: to_physical(title_bar.Height().ok()?),
+    })
+}
+
+/// Boxes a [`Color`] for the `ApplicationViewTitleBar` setters.
+///
+/// [`Color::SYSTEM_DEFAULT`] maps to `None`, which restores the system color.
+pub(crate) fn color_reference(color: Color) -> Option<IReference<UiColor>> {
+    if color == Color::SYSTEM_DEFAULT {
+        return None;
+    }
+    // `Color` uses the `COLORREF` layout, i.e. `0x00BBGGRR`.
+    let [r, g, b, _] = color.0.to_le_bytes();
+    Some(ColorReference(UiColor { A: 0xff, R: r, G: g, B: b }).into())
+}
+
+/// `IReference<Color>` implementation, WinRT has no built-in way to box structs.
+#[implement(IReference<UiColor>, IPropertyValue)]
+struct ColorReference(UiColor);
+
+impl IReference_Impl<UiColor> for ColorReference {
+    fn Value(&self) -> WinResult<UiColor> {
+        Ok(self.0)
+    }
+}
+
+macro_rules! type_mismatch {
+    ($($name:ident() -> $ty:ty;)*) => {
+        $(fn $name(&self) -> WinResult<$ty> {
+            Err(TYPE_E_TYPEMISMATCH.into())
+        })*
+    };
+    ($($name:ident(Array<$ty:ty>);)*) => {
+        $(fn $name(&self, _value: &mut Array<$ty>) -> WinResult<()> {
+            Err(TYPE_E_TYPEMISMATCH.into())
+        })*
+    };
+}
+
+impl IPropertyValue_Impl for ColorReference {
+    fn Type(&self) -> WinResult<PropertyType> {
+        Ok(PropertyType::OtherType)
+    }
+
+    fn IsNumericScalar(&self) -> WinResult<bool> {
+        Ok(false)
+    }
+
+    type_mismatch! {
+        GetUInt8() -> u8;
+        GetInt16() -> i16;
+        GetUInt16() -> u16;
+        GetInt32() -> i32;
+        GetUInt32() -> u32;
+        GetInt64() -> i64;
+        GetUInt64() -> u64;
+        GetSingle() -> f32;
+        GetDouble() -> f64;
+        GetChar16() -> u16;
+        GetBoolean() -> bool;
+        GetString() -> HSTRING;
+        GetGuid() -> GUID;
+        GetDateTime() -> DateTime;
+        GetTimeSpan() -> TimeSpan;
+        GetPoint() -> Point;
+        GetSize() -> Size;
+        GetRect() -> Rect;
+    }
+
+    type_mismatch! {
+        GetUInt8Array(Array<u8>);
+        GetInt16Array(Array<i16>);
+        GetUInt16Array(Array<u16>);
+        GetInt32Array(Array<i32>);
+        GetUInt32Array(Array<u32>);
+        GetInt64Array(Array<i64>);
+        GetUInt64Array(Array<u64>);
+        GetSingleArray(Array<f32>);
+        GetDoubleArray(Array<f64>);
+        GetChar16Array(Array<u16>);
+        GetBooleanArray(Array<bool>);
+        GetStringArray(Array<HSTRING>);
+        GetInspectableArray(Array<windows::core::IInspectable>);
+        GetGuidArray(Array<GUID>);
+        GetDateTimeArray(Array<DateTime>);
+        GetTimeSpanArray(Array<TimeSpan>);
+        GetPointArray(Array<Point>);
+        GetSizeArray(Array<Size>);
+        GetRectArray(Array<Rect>);
+    }
+}
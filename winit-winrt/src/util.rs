@@ -1,9 +1,125 @@
-use windows::Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
+use windows::Foundation::Rect;
+use windows::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
+use windows::Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED, RO_INIT_SINGLETHREADED};
+use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyState;
+use windows::Win32::UI::WindowsAndMessaging::{
+    SystemParametersInfoW, SPI_GETWHEELSCROLLCHARS, SPI_GETWHEELSCROLLLINES,
+    SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+};
+
+use crate::Apartment;
+
+/// The `Apartment` threads are initialized with, set once when the event loop is created.
+static APARTMENT: AtomicU8 = AtomicU8::new(Apartment::MultiThreaded as u8);
+
+pub(crate) fn set_apartment(apartment: Apartment) {
+    APARTMENT.store(apartment as u8, Ordering::Relaxed);
+}
 
 pub(crate) fn ensure_winrt_initialized() {
+    let init_type = match APARTMENT.load(Ordering::Relaxed) {
+        0 => RO_INIT_MULTITHREADED,
+        1 => RO_INIT_SINGLETHREADED,
+        _ => return,
+    };
     // Many WinRT calls (including `AgileReference::resolve`) require the calling thread to have
     // initialized the Windows Runtime. Calling this multiple times is fine; errors indicate the
     // apartment type was already set up (which is also fine for our purposes).
-    let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+    let _ = unsafe { RoInitialize(init_type) };
+}
+
+/// Convert a rectangle in view-independent pixels to physical pixels.
+pub(crate) fn rect_to_physical(
+    rect: Rect,
+    scale_factor: f64,
+) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let position = LogicalPosition::new(rect.X as f64, rect.Y as f64).to_physical(scale_factor);
+    let size = LogicalSize::new(rect.Width as f64, rect.Height as f64).to_physical(scale_factor);
+    (position, size)
+}
+
+/// Converts a `QueryPerformanceCounter` value to the time since boot.
+pub(crate) fn qpc_to_duration(ticks: i64) -> Option<Duration> {
+    let mut frequency = 0;
+    unsafe { QueryPerformanceFrequency(&mut frequency) }.ok()?;
+    ticks_to_duration(ticks, frequency)
+}
+
+fn ticks_to_duration(ticks: i64, frequency: i64) -> Option<Duration> {
+    let ticks = u64::try_from(ticks).ok()?;
+    let frequency = u64::try_from(frequency).ok().filter(|&frequency| frequency > 0)?;
+    let nanos = (ticks % frequency) * 1_000_000_000 / frequency;
+    Some(Duration::from_secs(ticks / frequency) + Duration::from_nanos(nanos))
 }
 
+/// Returns the current time since boot, in the time base of `PointerPoint::Timestamp`.
+pub(crate) fn current_timestamp() -> Option<Duration> {
+    let mut ticks = 0;
+    unsafe { QueryPerformanceCounter(&mut ticks) }.ok()?;
+    qpc_to_duration(ticks)
+}
+
+/// Returns the lines (or, for horizontal wheels, characters) to scroll per notch of the mouse
+/// wheel, as set by the user.
+pub(crate) fn wheel_scroll_lines(horizontal: bool) -> f32 {
+    /// The default of Windows, also used if the user chose to scroll a page at a time.
+    const DEFAULT_LINES: u32 = 3;
+    const WHEEL_PAGESCROLL: u32 = u32::MAX;
+
+    let action = if horizontal { SPI_GETWHEELSCROLLCHARS } else { SPI_GETWHEELSCROLLLINES };
+    let mut lines = DEFAULT_LINES;
+    let result = unsafe {
+        SystemParametersInfoW(
+            action,
+            0,
+            Some(&mut lines as *mut u32 as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    match result {
+        Ok(()) if lines != WHEEL_PAGESCROLL => lines as f32,
+        _ => DEFAULT_LINES as f32,
+    }
+}
+
+/// Returns whether the key with the given virtual key code is down, as seen by the messages the
+/// calling thread retrieved so far.
+pub(crate) fn key_state_down(virtual_key: i32) -> bool {
+    let state = unsafe { GetKeyState(virtual_key) };
+    state < 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_to_duration_table() {
+        let table = [
+            (0, 10_000_000, Some(Duration::ZERO)),
+            (10_000_000, 10_000_000, Some(Duration::from_secs(1))),
+            (15_000_001, 10_000_000, Some(Duration::new(1, 500_000_100))),
+            (3, 3_000_000, Some(Duration::from_micros(1))),
+            // Large tick counts don't overflow.
+            (i64::MAX, 10_000_000, Some(Duration::new(922_337_203_685, 477_580_700))),
+            (-1, 10_000_000, None),
+            (1, 0, None),
+            (1, -1, None),
+        ];
+        for (ticks, frequency, expected) in table {
+            assert_eq!(ticks_to_duration(ticks, frequency), expected, "{ticks} at {frequency} Hz");
+        }
+    }
+
+    #[test]
+    fn qpc_to_duration_round_trips() {
+        let mut frequency = 0;
+        unsafe { QueryPerformanceFrequency(&mut frequency) }.unwrap();
+        assert_eq!(qpc_to_duration(frequency * 2), Some(Duration::from_secs(2)));
+        assert_eq!(qpc_to_duration(-1), None);
+    }
+}
@@ -1,31 +1,72 @@
+use std::num::NonZeroIsize;
 use std::ptr::NonNull;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
-use dpi::{LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
-use windows::core::Interface;
-use windows::Foundation::Size as WinRtSize;
-use windows::UI::Core::{CoreCursor, CoreCursorType, CoreWindow as WinRtCoreWindow};
-use windows::UI::ViewManagement::ApplicationView;
+use dpi::{
+    LogicalPosition, LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size,
+};
+use tracing::warn;
+use windows::core::{AgileReference, IInspectable, Interface, HSTRING};
+use windows::Foundation::{Point, Rect, Size as WinRtSize};
+use windows::Graphics::Display::Core::HdmiDisplayInformation;
+use windows::Graphics::Holographic::{HolographicCamera, HolographicSpace};
+use windows::System::Display::DisplayRequest;
+use windows::Win32::System::WinRT::ICoreWindowInterop;
+use windows::UI::Composition::CompositionTarget;
+use windows::UI::Core::{
+    CoreCursor, CoreCursorType, CoreWindow as WinRtCoreWindow, CoreWindowActivationMode,
+};
+use windows::UI::ViewManagement::{
+    ApplicationView, ApplicationViewBoundsMode, ApplicationViewMode, ApplicationViewSwitcher,
+    InputPane, UIViewSettings, UserInteractionMode,
+};
 use winit_core::cursor::Cursor;
 use winit_core::error::{NotSupportedError, RequestError};
-use winit_core::event::WindowEvent;
-use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
+use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle};
 use winit_core::window::{
     CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme,
     UserAttentionType, Window as CoreWindowTrait, WindowAttributes, WindowButtons, WindowId,
     WindowLevel,
 };
 
-use crate::cursor::cursor_icon_to_core;
+use crate::app_window::AppWindowHost;
+use crate::cursor::{cursor_icon_to_core, WinRtCustomCursor};
+use crate::drag_drop;
+use crate::dxgi;
 use crate::event_loop::Runner;
-use crate::util::ensure_winrt_initialized;
+use crate::monitor::{current_hdmi_video_mode, find_hdmi_display_mode};
+use crate::orientation;
+use crate::panel::PanelHost;
+use crate::title_bar::{self, color_reference, core_title_bar};
+use crate::util::{ensure_winrt_initialized, rect_to_physical};
+use crate::{
+    Color, DragData, FrameStatistics, SurfaceRotation, TitleBarMetrics, VisibleBounds,
+    WindowAttributesWinRt,
+};
 
 pub struct Window {
+    state: Arc<WindowState>,
+    /// The panel hosting the window, see `WindowAttributesWinRt::with_swap_chain_panel`.
+    panel: Option<PanelHost>,
+    /// The `AppWindow` of the window, see `WindowAttributesWinRt::with_app_window`.
+    app_window: Option<AppWindowHost>,
+}
+
+/// The state of a `Window` which is shared with the functions it runs on the view thread.
+struct WindowState {
     runner: Arc<Runner>,
     id: WindowId,
     cursor_visible: AtomicBool,
-    cursor_icon: Mutex<CoreCursorType>,
+    /// The cursor type and, for `CoreCursorType::Custom`, the resource ID.
+    cursor_icon: Mutex<(CoreCursorType, u32)>,
+    cursor_grab: Mutex<CursorGrabMode>,
+    /// Whether the HDMI display mode was switched for exclusive fullscreen.
+    exclusive_fullscreen: AtomicBool,
+    /// The active `DisplayRequest`, see `WindowExtWinRt::set_display_keep_awake`.
+    display_request: Mutex<Option<AgileReference<DisplayRequest>>>,
+    /// Size set with `Window::set_min_surface_size`, restored once the window is resizable again.
+    min_surface_size: Mutex<Option<Size>>,
 }
 
 impl std::fmt::Debug for Window {
@@ -37,36 +78,422 @@ impl std::fmt::Debug for Window {
 impl Window {
     pub(crate) fn new(
         runner: Arc<Runner>,
-        _attributes: WindowAttributes,
+        mut attributes: WindowAttributes,
     ) -> Result<Self, RequestError> {
+        if runner.core_window().is_none() && !runner.headless && !runner.app_sdk {
+            return Err(NotSupportedError::new("CoreWindow is not available yet").into());
+        }
+
+        let platform = attributes
+            .platform
+            .take()
+            .and_then(|attrs| attrs.cast::<WindowAttributesWinRt>().ok())
+            .map(|attrs| *attrs)
+            .unwrap_or_default();
+        let swap_chain_panel = platform.swap_chain_panel;
+        // Without `CoreApplication`, the window can only be the application's `AppWindow`.
+        let app_window = match platform.app_window {
+            Some(hwnd) if runner.app_sdk => Some(hwnd),
+            Some(_) => {
+                return Err(NotSupportedError::new(
+                    "AppWindow hosting requires running on a DispatcherQueue",
+                )
+                .into())
+            },
+            None if runner.app_sdk => {
+                return Err(NotSupportedError::new(
+                    "running on a DispatcherQueue requires the HWND of an AppWindow",
+                )
+                .into())
+            },
+            None => None,
+        };
+        // Only XAML applications have panels, and they own the view.
+        if swap_chain_panel.is_some() && !runner.attached {
+            return Err(NotSupportedError::new(
+                "SwapChainPanel hosting requires attaching to the current view",
+            )
+            .into());
+        }
+
+        // The flag is cleared again when the window is dropped.
         if runner.window_created.swap(true, Ordering::SeqCst) {
             return Err(NotSupportedError::new("WinRT only supports a single window").into());
         }
 
-        if runner.core_window().is_none() {
-            return Err(NotSupportedError::new("CoreWindow is not available yet").into());
-        }
+        let app_window = match app_window.map(|hwnd| AppWindowHost::attach(&runner, hwnd)) {
+            Some(Ok(host)) => Some(host),
+            Some(Err(err)) => {
+                runner.window_created.store(false, Ordering::SeqCst);
+                return Err(os_error!(err).into());
+            },
+            None => None,
+        };
+        // An `AppWindow` is identified by its `HWND`, so the ID is only known once it is attached.
+        let id = runner.window_id();
+        let panel = match swap_chain_panel {
+            Some(panel) => match runner.on_view_thread({
+                let runner = Arc::clone(&runner);
+                move || PanelHost::attach(&runner, id, &panel)
+            }) {
+                Some(Ok(host)) => Some(host),
+                result => {
+                    if let Some(app_window) = app_window {
+                        app_window.detach();
+                    }
+                    runner.window_created.store(false, Ordering::SeqCst);
+                    return Err(match result {
+                        Some(Err(err)) => os_error!(err).into(),
+                        _ => NotSupportedError::new("the view thread is not available").into(),
+                    });
+                },
+            },
+            None => None,
+        };
 
-        Ok(Self {
+        let state = WindowState {
+            id,
             runner,
-            id: WindowId::from_raw(0),
             cursor_visible: AtomicBool::new(true),
-            cursor_icon: Mutex::new(CoreCursorType::Arrow),
+            cursor_icon: Mutex::new((CoreCursorType::Arrow, 0)),
+            cursor_grab: Mutex::new(CursorGrabMode::None),
+            exclusive_fullscreen: AtomicBool::new(false),
+            display_request: Mutex::new(None),
+            min_surface_size: Mutex::new(None),
+        };
+        let window = Self { state: Arc::new(state), panel, app_window };
+
+        // The system shows the title next to the application's display name.
+        if !attributes.title.is_empty() {
+            window.set_title(&attributes.title);
+        }
+        if !attributes.decorations {
+            window.set_decorations(false);
+        }
+        if !attributes.resizable {
+            window.set_resizable(false);
+        }
+        if attributes.window_level != WindowLevel::Normal {
+            window.set_window_level(attributes.window_level);
+        }
+        if attributes.preferred_theme.is_some() {
+            window.set_theme(attributes.preferred_theme);
+        }
+
+        Ok(window)
+    }
+
+    /// Logs that `method` isn't supported if the window is an `AppWindow`, whose `ApplicationView`
+    /// and title bar don't exist.
+    fn unsupported_for_app_window(&self, method: &str) -> bool {
+        if self.app_window.is_some() {
+            warn!("{method} is not supported for an AppWindow");
+        }
+        self.app_window.is_some()
+    }
+
+    /// Runs `f` on the view thread, see `Runner::on_view_thread`.
+    fn on_view_thread<R: Send + Default + 'static>(
+        &self,
+        f: impl FnOnce(&WindowState) -> R + Send + 'static,
+    ) -> R {
+        self.try_on_view_thread(f).unwrap_or_default()
+    }
+
+    /// Like `on_view_thread`, but returns `None` if `f` couldn't run.
+    fn try_on_view_thread<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&WindowState) -> R + Send + 'static,
+    ) -> Option<R> {
+        let state = Arc::clone(&self.state);
+        self.state.runner.on_view_thread(move || f(&state))
+    }
+
+    pub(crate) fn core_window(&self) -> Option<WinRtCoreWindow> {
+        self.state.runner.core_window()
+    }
+
+    pub(crate) fn swap_chain_panel(&self) -> Option<IInspectable> {
+        self.panel.as_ref()?.panel()
+    }
+
+    pub(crate) fn visible_bounds(&self) -> Option<VisibleBounds> {
+        let visible = self.visible_rect()?;
+        let scale_factor = self.scale_factor();
+        let logical_position = LogicalPosition::new(visible.X as f64, visible.Y as f64);
+        let logical_size = LogicalSize::new(visible.Width as f64, visible.Height as f64);
+        Some(VisibleBounds {
+            logical_position,
+            logical_size,
+            physical_position: logical_position.to_physical(scale_factor),
+            physical_size: logical_size.to_physical(scale_factor),
+        })
+    }
+
+    pub(crate) fn set_input_pane_visible(&self, visible: bool) -> bool {
+        self.on_view_thread(move |_| {
+            ensure_winrt_initialized();
+            let Ok(input_pane) = InputPane::GetForCurrentView() else {
+                return false;
+            };
+            let result = if visible { input_pane.TryShow() } else { input_pane.TryHide() };
+            result.unwrap_or(false)
+        })
+    }
+
+    pub(crate) fn set_key_interception(&self, intercept: bool) -> bool {
+        self.on_view_thread(move |state| state.runner.set_key_interception(state.id, intercept))
+    }
+
+    pub(crate) fn start_drag(&self, data: DragData) -> bool {
+        self.on_view_thread(move |state| match state.runner.pressed_pointer() {
+            Some(pointer_id) => drag_drop::start_drag(pointer_id, data),
+            None => false,
+        })
+    }
+
+    pub(crate) fn activate(&self) {
+        self.on_view_thread(|state| {
+            if let Some(window) = state.runner.core_window() {
+                let _ = window.Activate();
+            }
+        })
+    }
+
+    pub(crate) fn close(&self) {
+        self.on_view_thread(|state| state.runner.close_view(state.id))
+    }
+
+    pub(crate) fn set_extend_beyond_visible_bounds(&self, extend: bool) -> bool {
+        self.on_view_thread(move |state| {
+            ensure_winrt_initialized();
+            let Ok(view) = ApplicationView::GetForCurrentView() else {
+                return false;
+            };
+            let mode = if extend {
+                ApplicationViewBoundsMode::UseCoreWindow
+            } else {
+                ApplicationViewBoundsMode::UseVisible
+            };
+            let accepted = view.SetDesiredBoundsMode(mode).unwrap_or(false);
+            if accepted {
+                state.runner.refresh_surface_size(state.id);
+            }
+            accepted
+        })
+    }
+
+    pub(crate) fn extends_beyond_visible_bounds(&self) -> bool {
+        self.on_view_thread(|_| {
+            ensure_winrt_initialized();
+            ApplicationView::GetForCurrentView()
+                .and_then(|view| view.DesiredBoundsMode())
+                .is_ok_and(|mode| mode == ApplicationViewBoundsMode::UseCoreWindow)
+        })
+    }
+
+    pub(crate) fn input_pane_occluded_rect(
+        &self,
+    ) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+        self.on_view_thread(|state| {
+            ensure_winrt_initialized();
+            let rect = InputPane::GetForCurrentView().ok()?.OccludedRect().ok()?;
+            if rect.Width <= 0.0 || rect.Height <= 0.0 {
+                return None;
+            }
+            Some(rect_to_physical(rect, state.scale_factor()))
+        })
+    }
+
+    pub(crate) fn set_title_background_color(&self, color: Color) {
+        self.on_view_thread(move |_| {
+            let Some(title_bar) = title_bar::title_bar() else {
+                return;
+            };
+            let color = color_reference(color);
+            let _ = title_bar.SetBackgroundColor(color.as_ref());
+            let _ = title_bar.SetButtonBackgroundColor(color.as_ref());
         })
     }
 
-    pub(crate) fn core_window(&self) -> WinRtCoreWindow {
-        self.runner.core_window().expect("CoreWindow must be available on WinRT")
+    pub(crate) fn set_title_text_color(&self, color: Color) {
+        self.on_view_thread(move |_| {
+            let Some(title_bar) = title_bar::title_bar() else {
+                return;
+            };
+            let color = color_reference(color);
+            let _ = title_bar.SetForegroundColor(color.as_ref());
+            let _ = title_bar.SetButtonForegroundColor(color.as_ref());
+        })
+    }
+
+    pub(crate) fn title_bar_metrics(&self) -> Option<TitleBarMetrics> {
+        self.on_view_thread(|state| title_bar::title_bar_metrics(state.scale_factor()))
+    }
+
+    pub(crate) fn frame_statistics(&self) -> Option<FrameStatistics> {
+        dxgi::frame_statistics()
+    }
+
+    pub(crate) fn surface_rotation(&self) -> Option<SurfaceRotation> {
+        orientation::surface_rotation(&self.state.runner.display_info(self.state.id)?)
+    }
+
+    pub(crate) fn set_display_keep_awake(&self, keep_awake: bool) {
+        self.on_view_thread(move |state| {
+            ensure_winrt_initialized();
+            let mut display_request = state.display_request.lock().unwrap();
+            if keep_awake == display_request.is_some() {
+                return;
+            }
+            if keep_awake {
+                // Every `RequestActive` has to be balanced by a `RequestRelease` on the same
+                // object.
+                let request = DisplayRequest::new().and_then(|request| {
+                    let agile = AgileReference::new(&request)?;
+                    request.RequestActive()?;
+                    Ok(agile)
+                });
+                *display_request = request.ok();
+            } else if let Some(request) =
+                display_request.take().and_then(|request| request.resolve().ok())
+            {
+                let _ = request.RequestRelease();
+            }
+        })
+    }
+
+    pub(crate) fn composition_target(&self) -> Option<CompositionTarget> {
+        self.on_view_thread(|state| {
+            state
+                .runner
+                .composition_target()
+                .inspect_err(|err| warn!("failed to create the composition target: {err}"))
+                .ok()
+        })
+    }
+
+    pub(crate) fn application_view(&self) -> Option<ApplicationView> {
+        self.on_view_thread(|_| {
+            ensure_winrt_initialized();
+            ApplicationView::GetForCurrentView().ok()
+        })
+    }
+
+    pub(crate) fn hwnd(&self) -> Option<NonZeroIsize> {
+        if let Some(app_window) = &self.app_window {
+            return Some(app_window.hwnd());
+        }
+        let interop = self.state.runner.core_window()?.cast::<ICoreWindowInterop>().ok()?;
+        let hwnd = unsafe { interop.WindowHandle() }.ok()?;
+        NonZeroIsize::new(hwnd.0)
+    }
+
+    pub(crate) fn holographic_space(&self) -> Option<HolographicSpace> {
+        self.state.runner.holographic_space()
+    }
+
+    pub(crate) fn holographic_camera(&self, camera_id: u32) -> Option<HolographicCamera> {
+        self.state.runner.holographic_camera(camera_id)
+    }
+
+    /// Applies the minimum size, which is the pinned size while the window isn't resizable.
+    fn update_preferred_min_size(&self) {
+        self.on_view_thread(|state| {
+            ensure_winrt_initialized();
+            let Ok(view) = ApplicationView::GetForCurrentView() else {
+                return;
+            };
+            let logical = state.runner.pinned_surface_size(state.id).unwrap_or_else(|| {
+                state
+                    .min_surface_size
+                    .lock()
+                    .unwrap()
+                    .unwrap_or_else(|| Size::new(LogicalSize::new(0.0, 0.0)))
+                    .to_logical::<f64>(state.scale_factor())
+            });
+            let winrt_size =
+                WinRtSize { Width: logical.width as f32, Height: logical.height as f32 };
+            if let Err(err) = view.SetPreferredMinSize(winrt_size) {
+                warn!("Failed to set the preferred minimum size: {err}");
+            }
+        })
+    }
+
+    fn visible_rect(&self) -> Option<Rect> {
+        self.on_view_thread(|_| visible_rect())
+    }
+
+    /// Apply the current cursor icon, visibility and grab state to the `CoreWindow`.
+    fn update_core_cursor(&self) {
+        self.on_view_thread(|state| state.update_core_cursor())
+    }
+}
+
+impl WindowState {
+    fn scale_factor(&self) -> f64 {
+        self.runner.scale_factor(self.id)
     }
 
-    fn set_core_cursor(&self, cursor_type: CoreCursorType) {
-        let Ok(cursor) = CoreCursor::CreateCursor(cursor_type, 0) else {
+    fn set_core_cursor(&self, cursor_type: CoreCursorType, resource_id: u32) {
+        let Ok(cursor) = CoreCursor::CreateCursor(cursor_type, resource_id) else {
             return;
         };
         if let Some(window) = self.runner.core_window() {
             let _ = window.SetPointerCursor(&cursor);
         }
     }
+
+    /// Must be called on the view thread, see `Window::update_core_cursor`.
+    fn update_core_cursor(&self) {
+        // Removing the cursor also switches the mouse to relative mode, in which `MouseMoved`
+        // keeps reporting deltas while the cursor stays in place.
+        let locked = *self.cursor_grab.lock().unwrap() == CursorGrabMode::Locked;
+        if self.cursor_visible.load(Ordering::SeqCst) && !locked {
+            let (cursor_type, resource_id) = *self.cursor_icon.lock().unwrap();
+            self.set_core_cursor(cursor_type, resource_id);
+        } else if let Some(window) = self.runner.core_window() {
+            let _ = window.SetPointerCursor(None::<&CoreCursor>);
+        }
+    }
+}
+
+/// The visible bounds of the view, must be called on the view thread.
+fn visible_rect() -> Option<Rect> {
+    ensure_winrt_initialized();
+    ApplicationView::GetForCurrentView().ok()?.VisibleBounds().ok()
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        // The `CoreWindow` outlives the `Window`, so undo the changes a new window would
+        // otherwise inherit.
+        let _ = self.set_cursor_grab(CursorGrabMode::None);
+        *self.state.cursor_icon.lock().unwrap() = (CoreCursorType::Arrow, 0);
+        self.set_cursor_visible(true);
+        if self.state.exclusive_fullscreen.load(Ordering::SeqCst) {
+            if let Ok(hdi) = HdmiDisplayInformation::GetForCurrentView() {
+                let _ = hdi.SetDefaultDisplayModeAsync();
+            }
+        }
+        if self.state.runner.theme_override(self.state.id).is_some() {
+            self.set_theme(None);
+        }
+        if self.state.runner.pinned_surface_size(self.state.id).is_some() {
+            self.set_resizable(true);
+        }
+        self.set_display_keep_awake(false);
+        if let Some(panel) = self.panel.take() {
+            self.on_view_thread(move |state| panel.detach(&state.runner));
+        }
+        if let Some(app_window) = self.app_window.take() {
+            // The subclass can only be removed on the thread of the `AppWindow`, until then it
+            // ignores the messages of the window.
+            app_window.stop();
+            self.state.runner.on_dispatcher_queue_thread(move || app_window.detach());
+        }
+        self.state.runner.destroy_window(self.state.id);
+    }
 }
 
 impl rwh_06::HasDisplayHandle for Window {
@@ -78,7 +505,26 @@ impl rwh_06::HasDisplayHandle for Window {
 
 impl rwh_06::HasWindowHandle for Window {
     fn window_handle(&self) -> Result<rwh_06::WindowHandle<'_>, rwh_06::HandleError> {
-        let Some(window) = self.runner.core_window() else {
+        // Holographic frames are rendered through the `HolographicSpace`, a swapchain can't be
+        // created for the `CoreWindow`.
+        if self.state.runner.holographic {
+            return Err(rwh_06::HandleError::NotSupported);
+        }
+        // `raw-window-handle` has no handle for panels, so the `WinRt` handle carries the
+        // `IInspectable` of the panel instead. Renderers tell them apart by querying for
+        // `ISwapChainPanelNative`.
+        if let Some(panel) = &self.panel {
+            let Some(raw) = NonNull::new(panel.raw()) else {
+                return Err(rwh_06::HandleError::Unavailable);
+            };
+            let handle = rwh_06::WinRtWindowHandle::new(raw);
+            return Ok(unsafe { rwh_06::WindowHandle::borrow_raw(handle.into()) });
+        }
+        if let Some(app_window) = &self.app_window {
+            let handle = rwh_06::Win32WindowHandle::new(app_window.hwnd());
+            return Ok(unsafe { rwh_06::WindowHandle::borrow_raw(handle.into()) });
+        }
+        let Some(window) = self.state.runner.core_window() else {
             return Err(rwh_06::HandleError::Unavailable);
         };
         let raw = window.as_raw();
@@ -92,40 +538,53 @@ impl rwh_06::HasWindowHandle for Window {
 
 impl CoreWindowTrait for Window {
     fn id(&self) -> WindowId {
-        self.id
+        self.state.id
     }
 
     fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
-        Some(self.runner.monitor_handle().to_core())
+        Some(self.state.runner.monitor_handle(self.state.id).to_core())
     }
 
     fn available_monitors(&self) -> Box<dyn Iterator<Item = CoreMonitorHandle>> {
-        Box::new(std::iter::once(self.runner.monitor_handle().to_core()))
+        Box::new(std::iter::once(self.state.runner.monitor_handle(self.state.id).to_core()))
     }
 
     fn current_monitor(&self) -> Option<CoreMonitorHandle> {
-        Some(self.runner.monitor_handle().to_core())
+        Some(self.state.runner.monitor_handle(self.state.id).to_core())
     }
 
     fn scale_factor(&self) -> f64 {
-        self.runner.scale_factor()
+        self.state.scale_factor()
     }
 
     fn request_redraw(&self) {
-        self.runner.queue_window_event(WindowEvent::RedrawRequested);
-        self.runner.wake_up();
+        self.state.runner.request_redraw(self.state.id);
     }
 
-    fn pre_present_notify(&self) {}
+    fn pre_present_notify(&self) {
+        self.state.runner.pre_present_notify();
+    }
 
-    fn reset_dead_keys(&self) {}
+    fn reset_dead_keys(&self) {
+        self.state.runner.reset_dead_keys();
+    }
 
     fn surface_position(&self) -> PhysicalPosition<i32> {
         (0, 0).into()
     }
 
     fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError> {
-        Err(NotSupportedError::new("outer_position is not supported").into())
+        // The title bar isn't part of the `CoreWindow` bounds, so this is the position of the
+        // surface.
+        self.try_on_view_thread(|state| {
+            let bounds = state.runner.core_window()?.Bounds().ok()?;
+            Some(
+                LogicalPosition::new(bounds.X as f64, bounds.Y as f64)
+                    .to_physical::<i32>(state.scale_factor()),
+            )
+        })
+        .flatten()
+        .ok_or_else(|| NotSupportedError::new("CoreWindow is not available").into())
     }
 
     fn set_outer_position(&self, _position: Position) {
@@ -133,21 +592,29 @@ impl CoreWindowTrait for Window {
     }
 
     fn surface_size(&self) -> PhysicalSize<u32> {
-        self.runner.surface_size()
+        self.state.runner.surface_size(self.state.id)
     }
 
     fn request_surface_size(&self, size: Size) -> Option<PhysicalSize<u32>> {
-        ensure_winrt_initialized();
-        let scale_factor = self.scale_factor();
-        let logical = size.to_logical::<f64>(scale_factor);
-        if let Ok(view) = ApplicationView::GetForCurrentView() {
-            let winrt_size =
-                WinRtSize { Width: logical.width as f32, Height: logical.height as f32 };
-            if view.TryResizeView(winrt_size).ok().unwrap_or(false) {
-                return None;
-            }
+        // The XAML layout sizes the panel, and the application the `AppWindow`.
+        if self.panel.is_some() || self.app_window.is_some() {
+            return Some(self.surface_size());
         }
-        Some(self.surface_size())
+        self.on_view_thread(move |state| {
+            ensure_winrt_initialized();
+            let scale_factor = state.scale_factor();
+            let logical = size.to_logical::<f64>(scale_factor);
+            if let Ok(view) = ApplicationView::GetForCurrentView() {
+                let winrt_size =
+                    WinRtSize { Width: logical.width as f32, Height: logical.height as f32 };
+                match view.TryResizeView(winrt_size) {
+                    Ok(true) => return None,
+                    Ok(false) => {},
+                    Err(err) => warn!("Failed to resize the view: {err}"),
+                }
+            }
+            Some(state.runner.surface_size(state.id))
+        })
     }
 
     fn outer_size(&self) -> PhysicalSize<u32> {
@@ -155,53 +622,78 @@ impl CoreWindowTrait for Window {
     }
 
     fn safe_area(&self) -> PhysicalInsets<u32> {
-        ensure_winrt_initialized();
-        let Ok(view) = ApplicationView::GetForCurrentView() else {
+        // The XAML layout places the panel, the visible bounds are relative to the `CoreWindow`.
+        if self.panel.is_some() {
             return PhysicalInsets::new(0, 0, 0, 0);
-        };
-        let Ok(visible) = view.VisibleBounds() else {
-            return PhysicalInsets::new(0, 0, 0, 0);
-        };
-        let Ok(bounds) = self.core_window().Bounds() else {
-            return PhysicalInsets::new(0, 0, 0, 0);
-        };
-
-        let left = (visible.X - bounds.X).max(0.0) as f64;
-        let top = (visible.Y - bounds.Y).max(0.0) as f64;
-        let right = ((bounds.X + bounds.Width) - (visible.X + visible.Width)).max(0.0) as f64;
-        let bottom = ((bounds.Y + bounds.Height) - (visible.Y + visible.Height)).max(0.0) as f64;
-
-        let scale_factor = self.scale_factor();
-        PhysicalInsets::new(
-            (left * scale_factor).round() as u32,
-            (top * scale_factor).round() as u32,
-            (right * scale_factor).round() as u32,
-            (bottom * scale_factor).round() as u32,
-        )
+        }
+        self.on_view_thread(|state| {
+            let Some(visible) = visible_rect() else {
+                return PhysicalInsets::new(0, 0, 0, 0);
+            };
+            let Some(Ok(bounds)) = state.runner.core_window().map(|window| window.Bounds()) else {
+                return PhysicalInsets::new(0, 0, 0, 0);
+            };
+
+            let left = (visible.X - bounds.X).max(0.0) as f64;
+            let top = (visible.Y - bounds.Y).max(0.0) as f64;
+            let right = ((bounds.X + bounds.Width) - (visible.X + visible.Width)).max(0.0) as f64;
+            let bottom =
+                ((bounds.Y + bounds.Height) - (visible.Y + visible.Height)).max(0.0) as f64;
+
+            let scale_factor = state.scale_factor();
+            PhysicalInsets::new(
+                (left * scale_factor).round() as u32,
+                (top * scale_factor).round() as u32,
+                (right * scale_factor).round() as u32,
+                (bottom * scale_factor).round() as u32,
+            )
+        })
     }
 
     fn set_min_surface_size(&self, min_size: Option<Size>) {
-        ensure_winrt_initialized();
-        let Ok(view) = ApplicationView::GetForCurrentView() else {
+        *self.state.min_surface_size.lock().unwrap() = min_size;
+        self.update_preferred_min_size();
+    }
+
+    fn set_max_surface_size(&self, max_size: Option<Size>) {
+        // Enforced when the view is resized, see `Runner::handle_size_changed`.
+        self.state.runner.set_max_surface_size(self.state.id, max_size);
+        let size = self.surface_size().to_logical::<f64>(self.scale_factor());
+        let Some(clamped) = self.state.runner.clamped_surface_size(self.state.id, size) else {
             return;
         };
-        let scale_factor = self.scale_factor();
-        let logical = min_size
-            .unwrap_or_else(|| Size::new(LogicalSize::new(0.0, 0.0)))
-            .to_logical::<f64>(scale_factor);
-        let winrt_size = WinRtSize { Width: logical.width as f32, Height: logical.height as f32 };
-        let _ = view.SetPreferredMinSize(winrt_size);
+        self.on_view_thread(move |_| {
+            ensure_winrt_initialized();
+            let winrt_size =
+                WinRtSize { Width: clamped.width as f32, Height: clamped.height as f32 };
+            let resized = ApplicationView::GetForCurrentView()
+                .and_then(|view| view.TryResizeView(winrt_size));
+            if !matches!(resized, Ok(true)) {
+                warn!("The maximum surface size can't be enforced, the view can't be resized");
+            }
+        })
     }
 
-    fn set_max_surface_size(&self, _max_size: Option<Size>) {}
-
     fn surface_resize_increments(&self) -> Option<PhysicalSize<u32>> {
         None
     }
 
     fn set_surface_resize_increments(&self, _increments: Option<Size>) {}
 
-    fn set_title(&self, _title: &str) {}
+    fn set_title(&self, title: &str) {
+        if let Some(app_window) = &self.app_window {
+            return app_window.set_title(title);
+        }
+        let title = HSTRING::from(title);
+        self.on_view_thread(move |_| {
+            ensure_winrt_initialized();
+            if let Ok(view) = ApplicationView::GetForCurrentView() {
+                if let Err(err) = view.SetTitle(&title) {
+                    warn!("Failed to set the title: {err}");
+                }
+            }
+        })
+    }
 
     fn set_transparent(&self, _transparent: bool) {}
 
@@ -210,13 +702,35 @@ impl CoreWindowTrait for Window {
     fn set_visible(&self, _visible: bool) {}
 
     fn is_visible(&self) -> Option<bool> {
-        self.runner.core_window().and_then(|window| window.Visible().ok())
+        self.on_view_thread(|state| {
+            state.runner.core_window().and_then(|window| window.Visible().ok())
+        })
     }
 
-    fn set_resizable(&self, _resizable: bool) {}
+    fn set_resizable(&self, resizable: bool) {
+        let size = self.surface_size().to_logical::<f64>(self.scale_factor());
+        self.state.runner.set_pinned_surface_size(self.state.id, (!resizable).then_some(size));
+        self.update_preferred_min_size();
+    }
 
     fn is_resizable(&self) -> bool {
-        false
+        if let Some(app_window) = &self.app_window {
+            return app_window.is_resizable();
+        }
+        if self.state.runner.pinned_surface_size(self.state.id).is_some() {
+            return false;
+        }
+        // Views can only be resized as windows on the desktop, not in tablet mode or full screen.
+        self.on_view_thread(|_| {
+            ensure_winrt_initialized();
+            let in_window = UIViewSettings::GetForCurrentView()
+                .and_then(|settings| settings.UserInteractionMode())
+                .is_ok_and(|mode| mode == UserInteractionMode::Mouse);
+            let full_screen = ApplicationView::GetForCurrentView()
+                .and_then(|view| view.IsFullScreenMode())
+                .unwrap_or(false);
+            in_window && !full_screen
+        })
     }
 
     fn set_enabled_buttons(&self, _buttons: WindowButtons) {}
@@ -228,7 +742,19 @@ impl CoreWindowTrait for Window {
     fn set_minimized(&self, _minimized: bool) {}
 
     fn is_minimized(&self) -> Option<bool> {
-        None
+        self.on_view_thread(|state| {
+            let window = state.runner.core_window()?;
+            let visible = window.Visible().ok()?;
+            // A window hidden behind a full screen application is still activated. Before
+            // Windows 10, version 1803, `ActivationMode` isn't available and any hidden window is
+            // reported as minimized.
+            let activated = matches!(
+                window.ActivationMode(),
+                Ok(CoreWindowActivationMode::ActivatedInForeground
+                    | CoreWindowActivationMode::ActivatedNotForeground)
+            );
+            Some(!visible && !activated)
+        })
     }
 
     fn set_maximized(&self, _maximized: bool) {}
@@ -237,37 +763,106 @@ impl CoreWindowTrait for Window {
         false
     }
 
-    fn set_fullscreen(&self, monitor: Option<winit_core::monitor::Fullscreen>) {
-        ensure_winrt_initialized();
-        let Ok(view) = ApplicationView::GetForCurrentView() else {
+    fn set_fullscreen(&self, monitor: Option<Fullscreen>) {
+        if self.unsupported_for_app_window("Window::set_fullscreen") {
             return;
-        };
-        if monitor.is_some() {
-            let _ = view.TryEnterFullScreenMode();
-        } else {
-            let _ = view.ExitFullScreenMode();
         }
+        self.on_view_thread(move |state| {
+            ensure_winrt_initialized();
+            let Ok(view) = ApplicationView::GetForCurrentView() else {
+                return;
+            };
+
+            // Switching the display mode is only possible through HDMI, i.e. on Xbox. Elsewhere,
+            // exclusive fullscreen falls back to borderless fullscreen.
+            let hdmi_mode = match &monitor {
+                Some(Fullscreen::Exclusive(_, video_mode)) => find_hdmi_display_mode(video_mode),
+                _ => None,
+            };
+            if let Some((hdi, mode)) = hdmi_mode {
+                let _ = hdi.RequestSetCurrentDisplayModeAsync(&mode);
+                state.exclusive_fullscreen.store(true, Ordering::SeqCst);
+            } else if state.exclusive_fullscreen.swap(false, Ordering::SeqCst) {
+                if let Ok(hdi) = HdmiDisplayInformation::GetForCurrentView() {
+                    let _ = hdi.SetDefaultDisplayModeAsync();
+                }
+            }
+
+            if monitor.is_some() {
+                if let Err(err) = view.TryEnterFullScreenMode() {
+                    warn!("Failed to enter full screen mode: {err}");
+                }
+            } else if let Err(err) = view.ExitFullScreenMode() {
+                warn!("Failed to exit full screen mode: {err}");
+            }
+        })
     }
 
-    fn fullscreen(&self) -> Option<winit_core::monitor::Fullscreen> {
-        ensure_winrt_initialized();
-        let Ok(view) = ApplicationView::GetForCurrentView() else {
-            return None;
-        };
-        if view.IsFullScreenMode().ok().unwrap_or(false) {
-            Some(winit_core::monitor::Fullscreen::Borderless(None))
-        } else {
-            None
-        }
+    fn fullscreen(&self) -> Option<Fullscreen> {
+        self.on_view_thread(|state| {
+            ensure_winrt_initialized();
+            let Ok(view) = ApplicationView::GetForCurrentView() else {
+                return None;
+            };
+            if !view.IsFullScreenMode().ok().unwrap_or(false) {
+                return None;
+            }
+            // Report the mode the display actually switched to, which may not be the requested one.
+            if state.exclusive_fullscreen.load(Ordering::SeqCst) {
+                if let Some(video_mode) = current_hdmi_video_mode() {
+                    let monitor = state.runner.monitor_handle(state.id).to_core();
+                    return Some(Fullscreen::Exclusive(monitor, video_mode));
+                }
+            }
+            Some(Fullscreen::Borderless(None))
+        })
     }
 
-    fn set_decorations(&self, _decorations: bool) {}
+    fn set_decorations(&self, decorations: bool) {
+        if self.unsupported_for_app_window("Window::set_decorations") {
+            return;
+        }
+        self.on_view_thread(move |_| {
+            if let Some(title_bar) = core_title_bar() {
+                if let Err(err) = title_bar.SetExtendViewIntoTitleBar(!decorations) {
+                    warn!("Failed to extend the view into the title bar: {err}");
+                }
+            }
+        })
+    }
 
     fn is_decorated(&self) -> bool {
-        true
+        self.on_view_thread(|_| {
+            core_title_bar()
+                .and_then(|title_bar| title_bar.ExtendViewIntoTitleBar().ok())
+                .is_none_or(|extended| !extended)
+        })
     }
 
-    fn set_window_level(&self, _level: WindowLevel) {}
+    fn set_window_level(&self, level: WindowLevel) {
+        if self.unsupported_for_app_window("Window::set_window_level") {
+            return;
+        }
+        self.on_view_thread(move |_| {
+            ensure_winrt_initialized();
+            let Ok(view) = ApplicationView::GetForCurrentView() else {
+                return;
+            };
+            // A compact overlay view stays above other windows, which is the closest WinRT has to
+            // `AlwaysOnTop`.
+            let mode = match level {
+                WindowLevel::AlwaysOnTop => ApplicationViewMode::CompactOverlay,
+                WindowLevel::Normal => ApplicationViewMode::Default,
+                WindowLevel::AlwaysOnBottom => return,
+            };
+            if view.IsViewModeSupported(mode).unwrap_or(false) && view.ViewMode().ok() != Some(mode)
+            {
+                if let Err(err) = view.TryEnterViewModeAsync(mode) {
+                    warn!("Failed to change the view mode: {err}");
+                }
+            }
+        })
+    }
 
     fn set_window_icon(&self, _window_icon: Option<winit_core::icon::Icon>) {}
 
@@ -284,45 +879,88 @@ impl CoreWindowTrait for Window {
     fn set_ime_purpose(&self, _purpose: winit_core::window::ImePurpose) {}
 
     fn focus_window(&self) {
-        if let Some(window) = self.runner.core_window() {
-            let _ = window.Activate();
-        }
+        self.on_view_thread(|state| {
+            if state.runner.has_focus() {
+                return;
+            }
+            if let Some(window) = state.runner.core_window() {
+                let _ = window.Activate();
+            }
+            // `Activate` doesn't bring a view in the background forward. The switcher can, unless
+            // the system refuses to steal the focus from the foreground application.
+            ensure_winrt_initialized();
+            if let Ok(view_id) = ApplicationView::GetForCurrentView().and_then(|view| view.Id()) {
+                let _ = ApplicationViewSwitcher::TryShowAsStandaloneAsync(view_id);
+            }
+        })
     }
 
     fn has_focus(&self) -> bool {
-        self.runner.has_focus()
+        self.state.runner.has_focus()
     }
 
-    fn request_user_attention(&self, _request_type: Option<UserAttentionType>) {}
+    fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
+        match request_type {
+            Some(request_type) => {
+                self.state.runner.request_user_attention(request_type, &self.title())
+            },
+            None => self.state.runner.clear_user_attention(),
+        }
+    }
 
     fn set_cursor(&self, cursor: Cursor) {
-        if let Cursor::Icon(icon) = cursor {
-            let core = cursor_icon_to_core(icon);
-            *self.cursor_icon.lock().unwrap() = core;
-            if self.cursor_visible.load(Ordering::SeqCst) {
-                self.set_core_cursor(core);
-            }
-        }
+        let core = match cursor {
+            Cursor::Icon(icon) => (cursor_icon_to_core(icon), 0),
+            Cursor::Custom(cursor) => match cursor.cast_ref::<WinRtCustomCursor>() {
+                Some(cursor) => (CoreCursorType::Custom, cursor.resource_id),
+                None => return,
+            },
+        };
+        *self.state.cursor_icon.lock().unwrap() = core;
+        self.update_core_cursor();
     }
 
-    fn set_cursor_position(&self, _position: Position) -> Result<(), RequestError> {
-        Err(NotSupportedError::new("set_cursor_position is not supported").into())
+    fn set_cursor_position(&self, position: Position) -> Result<(), RequestError> {
+        self.try_on_view_thread(move |state| {
+            let Some(window) = state.runner.core_window() else {
+                return Err(NotSupportedError::new("CoreWindow is not available").into());
+            };
+
+            // `SetPointerPosition` takes screen coordinates in DIPs, so offset the
+            // surface-relative position by the window bounds.
+            let position = position.to_logical::<f64>(state.scale_factor());
+            let bounds = window.Bounds().map_err(|err| os_error!(err))?;
+            let point = Point { X: bounds.X + position.x as f32, Y: bounds.Y + position.y as f32 };
+            window.SetPointerPosition(point).map_err(|err| os_error!(err))?;
+            Ok(())
+        })
+        .unwrap_or_else(|| Err(NotSupportedError::new("the view is closed").into()))
     }
 
-    fn set_cursor_grab(&self, _mode: CursorGrabMode) -> Result<(), RequestError> {
-        Err(NotSupportedError::new("set_cursor_grab is not supported").into())
+    fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
+        self.try_on_view_thread(move |state| {
+            let Some(window) = state.runner.core_window() else {
+                return Err(NotSupportedError::new("CoreWindow is not available").into());
+            };
+
+            // Capturing the pointer keeps it routed to this window, which is the closest WinRT
+            // has to confining it.
+            match mode {
+                CursorGrabMode::Confined => window.SetPointerCapture(),
+                CursorGrabMode::None | CursorGrabMode::Locked => window.ReleasePointerCapture(),
+            }
+            .map_err(|err| os_error!(err))?;
+
+            *state.cursor_grab.lock().unwrap() = mode;
+            state.update_core_cursor();
+            Ok(())
+        })
+        .unwrap_or_else(|| Err(NotSupportedError::new("the view is closed").into()))
     }
 
     fn set_cursor_visible(&self, visible: bool) {
-        self.cursor_visible.store(visible, Ordering::SeqCst);
-        if visible {
-            let icon = *self.cursor_icon.lock().unwrap();
-            self.set_core_cursor(icon);
-        } else {
-            if let Some(window) = self.runner.core_window() {
-                let _ = window.SetPointerCursor(None::<&CoreCursor>);
-            }
-        }
+        self.state.cursor_visible.store(visible, Ordering::SeqCst);
+        self.update_core_cursor();
     }
 
     fn drag_window(&self) -> Result<(), RequestError> {
@@ -339,22 +977,49 @@ impl CoreWindowTrait for Window {
         Err(NotSupportedError::new("set_cursor_hittest is not supported").into())
     }
 
-    fn set_theme(&self, _theme: Option<Theme>) {}
+    fn set_theme(&self, theme: Option<Theme>) {
+        self.state.runner.set_theme_override(self.state.id, theme);
+        // UWP has no per-window theme, only the title bar follows the override.
+        let (background, text) = match theme {
+            Some(Theme::Dark) => {
+                (Color::from_rgb(0x20, 0x20, 0x20), Color::from_rgb(0xff, 0xff, 0xff))
+            },
+            Some(Theme::Light) => {
+                (Color::from_rgb(0xff, 0xff, 0xff), Color::from_rgb(0x00, 0x00, 0x00))
+            },
+            None => (Color::SYSTEM_DEFAULT, Color::SYSTEM_DEFAULT),
+        };
+        self.set_title_background_color(background);
+        self.set_title_text_color(text);
+    }
 
     fn theme(&self) -> Option<Theme> {
-        None
+        self.state.runner.theme_override(self.state.id).or_else(|| self.state.runner.system_theme())
     }
 
     fn set_content_protected(&self, protected: bool) {
-        ensure_winrt_initialized();
-        let Ok(view) = ApplicationView::GetForCurrentView() else {
-            return;
-        };
-        let _ = view.SetIsScreenCaptureEnabled(!protected);
+        self.on_view_thread(move |_| {
+            ensure_winrt_initialized();
+            let Ok(view) = ApplicationView::GetForCurrentView() else {
+                return;
+            };
+            if let Err(err) = view.SetIsScreenCaptureEnabled(!protected) {
+                warn!("Failed to change the screen capture protection: {err}");
+            }
+        })
     }
 
     fn title(&self) -> String {
-        String::new()
+        if let Some(app_window) = &self.app_window {
+            return app_window.title();
+        }
+        self.on_view_thread(|_| {
+            ensure_winrt_initialized();
+            ApplicationView::GetForCurrentView()
+                .and_then(|view| view.Title())
+                .map(|title| title.to_string_lossy())
+                .unwrap_or_default()
+        })
     }
 
     fn rwh_06_display_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
@@ -12,7 +12,7 @@ fn main() {
         macos_platform: { target_os = "macos" },
         ios_platform: { all(target_vendor = "apple", not(target_os = "macos")) },
         windows_platform: { target_os = "windows" },
-        winrt_platform: { all(windows_platform, __WINRT__) },
+        winrt_platform: { all(windows_platform, feature = "winrt") },
         free_unix: { all(unix, not(target_vendor = "apple"), not(android_platform), not(target_os = "emscripten")) },
         redox: { target_os = "redox" },
 
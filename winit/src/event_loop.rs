@@ -182,6 +182,10 @@ impl EventLoop {
     /// runs the event loop internally, and then returns once [`ActiveEventLoop::exit`] is called.
     /// See [`run_app_on_demand`] for more detailed semantics.
     ///
+    /// On WinRT, this runs the view with `CoreApplication::Run` and returns once the event loop
+    /// exited. `CoreApplication::Run` can only be called once per process, so the event loop can't
+    /// be run again afterwards.
+    ///
     /// On iOS, this will register the application handler, and then call [`UIApplicationMain`]
     /// (which is the only way to run the system event loop), which never returns to the caller
     /// (the process instead exits after the handler has been dropped). See also
@@ -243,7 +247,7 @@ impl EventLoop {
         }
         #[cfg(winrt_platform)]
         {
-            self.event_loop.run_app_never_return(app)
+            self.event_loop.run_app(app)
         }
     }
 
@@ -331,7 +335,7 @@ impl AsRawFd for EventLoop {
     android_platform,
     x11_platform,
     wayland_platform,
-    all(windows_platform, not(winrt_platform)),
+    windows_platform,
     docsrs,
 ))]
 impl winit_core::event_loop::pump_events::EventLoopExtPumpEvents for EventLoop {
@@ -374,6 +378,15 @@ impl winit_core::event_loop::never_return::EventLoopExtNeverReturn for EventLoop
     }
 }
 
+#[cfg(winrt_platform)]
+impl winit_winrt::EventLoopViewExtWinRt for EventLoop {
+    fn run_view_never_return<F: FnOnce(Self) + Send + 'static>(self, main: F) -> ! {
+        self.event_loop.run_view_never_return(move |event_loop| {
+            main(EventLoop { event_loop, _marker: PhantomData })
+        })
+    }
+}
+
 #[cfg(android_platform)]
 impl winit_android::EventLoopExtAndroid for EventLoop {
     fn android_app(&self) -> &winit_android::activity::AndroidApp {
@@ -496,6 +509,87 @@ impl winit_win32::EventLoopBuilderExtWindows for EventLoopBuilder {
     }
 }
 
+#[cfg(winrt_platform)]
+impl winit_winrt::EventLoopBuilderExtWinRt for EventLoopBuilder {
+    #[inline]
+    fn with_coalesced_pointer_history(&mut self, enabled: bool) -> &mut Self {
+        self.platform_specific.coalesced_pointer_history = enabled;
+        self
+    }
+
+    #[inline]
+    fn with_preferred_launch_mode(&mut self, mode: winit_winrt::LaunchWindowingMode) -> &mut Self {
+        self.platform_specific.preferred_launch_mode = Some(mode);
+        self
+    }
+
+    #[inline]
+    fn with_back_requested_as_close(&mut self, enabled: bool) -> &mut Self {
+        self.platform_specific.back_requested_as_close = enabled;
+        self
+    }
+
+    #[inline]
+    fn with_close_confirmation(&mut self, enabled: bool) -> &mut Self {
+        self.platform_specific.close_confirmation = enabled;
+        self
+    }
+
+    #[inline]
+    fn with_vsync_redraws(&mut self, enabled: bool) -> &mut Self {
+        self.platform_specific.vsync_redraws = enabled;
+        self
+    }
+
+    #[inline]
+    fn with_headless(&mut self, surface: winit_winrt::HeadlessSurface) -> &mut Self {
+        self.platform_specific.headless = Some(surface);
+        self
+    }
+
+    #[inline]
+    fn with_dispatcher_priority(&mut self, priority: winit_winrt::DispatcherPriority) -> &mut Self {
+        self.platform_specific.dispatcher_priority = priority;
+        self
+    }
+
+    #[inline]
+    fn with_apartment(&mut self, apartment: winit_winrt::Apartment) -> &mut Self {
+        self.platform_specific.apartment = apartment;
+        self
+    }
+
+    #[inline]
+    fn with_deferred_activation(&mut self, deferred: bool) -> &mut Self {
+        self.platform_specific.deferred_activation = deferred;
+        self
+    }
+
+    #[inline]
+    fn with_attach_to_current_view(&mut self, attach: bool) -> &mut Self {
+        self.platform_specific.attach_to_current_view = attach;
+        self
+    }
+
+    #[inline]
+    fn with_independent_input(&mut self, devices: winit_winrt::InputDevices) -> &mut Self {
+        self.platform_specific.independent_input = devices;
+        self
+    }
+
+    #[inline]
+    fn with_holographic(&mut self, holographic: bool) -> &mut Self {
+        self.platform_specific.holographic = holographic;
+        self
+    }
+
+    #[inline]
+    fn with_dispatcher_queue(&mut self, dispatcher_queue: bool) -> &mut Self {
+        self.platform_specific.dispatcher_queue = dispatcher_queue;
+        self
+    }
+}
+
 #[cfg(x11_platform)]
 impl winit_x11::EventLoopExtX11 for EventLoop {
     #[inline]
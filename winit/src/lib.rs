@@ -190,11 +190,16 @@
 //!
 //! Winit provides the following Cargo features:
 //!
+//! * `win32` (enabled by default): On Windows, enables the Win32 backend. Windows builds with the
+//!   default features disabled must enable it, or `winrt`.
 //! * `x11` (enabled by default): On Unix platforms, enables the X11 backend.
 //! * `wayland` (enabled by default): On Unix platforms, enables the Wayland backend.
 //! * `rwh_06`: Implement `raw-window-handle v0.6` traits.
 //! * `serde`: Enables serialization/deserialization of certain types with [Serde](https://crates.io/crates/serde).
 //! * `mint`: Enables mint (math interoperability standard types) conversions.
+//! * `winrt`: On Windows, selects the WinRT/UWP backend instead of Win32. Disable the default
+//!   features so that the Win32 backend isn't built as well. `cfg(__WINRT__)` no longer selects
+//!   the backend, and fails to compile without this feature.
 //!
 //! See the [`platform`] module for documentation on platform-specific cargo
 //! features.
@@ -269,7 +274,7 @@
 //! [`raw_window_handle`]: ./window/struct.Window.html#method.raw_window_handle
 //! [`raw_display_handle`]: ./window/struct.Window.html#method.raw_display_handle
 //! [`EventLoopExtPumpEvents::pump_app_events()`]: crate::event_loop::pump_events::EventLoopExtPumpEvents::pump_app_events()
-//! [^1]: `EventLoopExtPumpEvents::pump_app_events()` is only available on Windows, macOS, Android, X11 and Wayland.
+//! [^1]: `EventLoopExtPumpEvents::pump_app_events()` is only available on Windows, macOS, Android, X11, Wayland and WinRT.
 
 #![deny(rust_2018_idioms)]
 #![deny(rustdoc::broken_intra_doc_links)]
@@ -19,7 +19,7 @@
 //! ## Common modules
 //!
 //! Furthermore, we provide two modules for common functionality:
-//! - `scancode`, available on Windows, macOS, Wayland and X11.
+//! - `scancode`, available on Windows (Win32 and WinRT), macOS, Wayland and X11.
 //! - `startup_notify`, available on Wayland and X11.
 
 #[cfg(android_platform)]
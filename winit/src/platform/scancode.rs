@@ -12,7 +12,7 @@ pub trait PhysicalKeyExtScancode {
     /// Returns `Some(key_id)` if the conversion was successful; returns `None` otherwise.
     ///
     /// ## Platform-specific
-    /// - **Windows:** A 16bit extended scancode
+    /// - **Windows / WinRT:** A 16bit extended scancode
     /// - **Wayland/X11**: A 32-bit linux scancode, which is X11/Wayland keycode subtracted by 8.
     fn to_scancode(self) -> Option<u32>;
 
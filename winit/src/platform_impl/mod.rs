@@ -31,3 +31,12 @@ pub use self::platform::*;
     not(orbital_platform),
 ))]
 compile_error!("The platform you're compiling for is not supported by winit");
+
+#[cfg(all(windows_platform, not(winrt_platform), not(feature = "win32")))]
+compile_error!("On Windows, winit needs either the `win32` or the `winrt` feature");
+
+#[cfg(all(windows_platform, __WINRT__, not(feature = "winrt")))]
+compile_error!(
+    "`cfg(__WINRT__)` no longer selects the WinRT backend, enable the `winrt` feature of winit \
+     instead"
+);